@@ -0,0 +1,19 @@
+//! The memoized recursion at n = 14, with its memo keys on the heap
+//! (`pfaffian`) and in a bump arena (`pfaffian_arena`).
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use pfaffian::SkewMatrix;
+use std::hint::black_box;
+
+fn arena_vs_default(c: &mut Criterion) {
+    let values: Vec<f64> = (0..91).map(|k| ((k * 5 + 1) as f64).cos()).collect();
+    let matrix = SkewMatrix::from_upper_triangle(14, &values).unwrap();
+
+    let mut group = c.benchmark_group("recursive n=14");
+    group.bench_function("default", |b| b.iter(|| black_box(&matrix).pfaffian()));
+    group.bench_function("arena", |b| b.iter(|| black_box(&matrix).pfaffian_arena()));
+    group.finish();
+}
+
+criterion_group!(benches, arena_vs_default);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+//! The skew elimination at larger n, with its trailing update walking the
+//! matrix column by column (`log_pfaffian`) and, for comparison, the old
+//! pattern that read the multipliers from row k and added an m x m
+//! temporary.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use nalgebra::DMatrix;
+use pfaffian::SkewMatrix;
+use std::hint::black_box;
+
+/// ln|Pf(A)| by partial-pivoting elimination with the row-wise update.
+fn log_pfaffian_row_update(matrix: &SkewMatrix) -> f64 {
+    let mut a = matrix.as_matrix().clone();
+    let n = a.nrows();
+    let mut log_abs = 0.0;
+    for k in (0..n).step_by(2) {
+        let kp = (k + 1..n)
+            .max_by(|&r, &s| a[(r, k)].abs().total_cmp(&a[(s, k)].abs()))
+            .unwrap();
+        a.swap_rows(k + 1, kp);
+        a.swap_columns(k + 1, kp);
+        let pivot = a[(k, k + 1)];
+        log_abs += pivot.abs().ln();
+        if k + 2 < n {
+            let m = n - k - 2;
+            let tau = a.view((k, k + 2), (1, m)).transpose() / pivot;
+            let col = a.view((k + 2, k + 1), (m, 1)).into_owned();
+            let update = &tau * col.transpose() - &col * tau.transpose();
+            let mut trailing = a.view_mut((k + 2, k + 2), (m, m));
+            trailing += update;
+        }
+    }
+    log_abs
+}
+
+fn column_vs_row_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("elimination");
+    group.sample_size(10);
+    for n in [200, 600] {
+        let dense = DMatrix::from_fn(n, n, |i, j| ((i * 7 + j * 13) as f64).sin());
+        let matrix =
+            SkewMatrix::from_column_major(n, (&dense - dense.transpose()).as_slice(), 0.0).unwrap();
+        group.bench_with_input(BenchmarkId::new("column-major", n), &matrix, |b, m| {
+            b.iter(|| black_box(m).log_pfaffian())
+        });
+        group.bench_with_input(BenchmarkId::new("row update", n), &matrix, |b, m| {
+            b.iter(|| log_pfaffian_row_update(black_box(m)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, column_vs_row_update);
+criterion_main!(benches);
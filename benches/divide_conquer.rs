@@ -0,0 +1,29 @@
+//! The recursive block split against plain elimination, at sizes where
+//! the split recurses a few levels before reaching its base case.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use nalgebra::DMatrix;
+use pfaffian::SkewMatrix;
+use std::hint::black_box;
+
+fn divide_conquer_vs_elimination(c: &mut Criterion) {
+    let mut group = c.benchmark_group("divide and conquer");
+    group.sample_size(10);
+    for n in [512, 1024] {
+        let dense = DMatrix::from_fn(n, n, |i, j| ((i * 7 + j * 13) as f64).sin());
+        let matrix =
+            SkewMatrix::from_column_major(n, (&dense - dense.transpose()).as_slice(), 0.0).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("divide and conquer", n),
+            &matrix,
+            |b, m| b.iter(|| black_box(m).pfaffian_divide_conquer()),
+        );
+        group.bench_with_input(BenchmarkId::new("log_pfaffian", n), &matrix, |b, m| {
+            b.iter(|| black_box(m).log_pfaffian())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, divide_conquer_vs_elimination);
+criterion_main!(benches);
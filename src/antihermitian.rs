@@ -0,0 +1,100 @@
+use nalgebra::{Complex, DMatrix};
+
+use crate::{PfaffianError, PivotStrategy, SkewMatrix};
+
+/// A complex anti-Hermitian matrix, A = -A†.
+///
+/// This is not the same as a complex skew-symmetric matrix (A = -Aᵀ, no
+/// conjugation). A complex skew matrix has a genuine Pfaffian, a polynomial
+/// in its entries with Pf² = det, and `SkewMatrix<Complex<f64>>` computes
+/// it. An anti-Hermitian matrix is only skew when it is real: its diagonal
+/// is imaginary rather than zero, and A_ji = -conj(A_ij) rather than -A_ij,
+/// so Pf(A) itself isn't defined.
+///
+/// What it does have is a real skew form. Writing A = X + iY, X is real skew
+/// and Y is real symmetric, so M = [[X, -Y], [Y, X]] (the 2n x 2n real matrix
+/// of A acting on ℝ²ⁿ = ℂⁿ) is real skew-symmetric, for any n.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AntiHermitianMatrix {
+    data: DMatrix<Complex<f64>>,
+}
+
+impl AntiHermitianMatrix {
+    /// Checks that `data` is square and anti-Hermitian to within `tol`, and
+    /// stores the exact anti-Hermitian part (A - A†) / 2.
+    pub fn from_matrix(data: DMatrix<Complex<f64>>, tol: f64) -> Result<Self, PfaffianError> {
+        let (rows, cols) = data.shape();
+        if rows != cols {
+            return Err(PfaffianError::NotSquare { rows, cols });
+        }
+        for i in 0..rows {
+            for j in i..rows {
+                if (data[(i, j)] + data[(j, i)].conj()).norm() > tol {
+                    return Err(PfaffianError::NotAntiHermitian { row: i, col: j });
+                }
+            }
+        }
+
+        let exact = (&data - data.adjoint()) * Complex::new(0.5, 0.0);
+        Ok(Self { data: exact })
+    }
+
+    pub fn as_matrix(&self) -> &DMatrix<Complex<f64>> {
+        &self.data
+    }
+
+    /// The real skew form M = [[X, -Y], [Y, X]] described above.
+    pub fn real_skew_form(&self) -> SkewMatrix {
+        let n = self.data.nrows();
+        let x = self.data.map(|z| z.re);
+        let y = self.data.map(|z| z.im);
+        let mut m = DMatrix::<f64>::zeros(2 * n, 2 * n);
+        m.view_mut((0, 0), (n, n)).copy_from(&x);
+        m.view_mut((0, n), (n, n)).copy_from(&(-&y));
+        m.view_mut((n, 0), (n, n)).copy_from(&y);
+        m.view_mut((n, n), (n, n)).copy_from(&x);
+        SkewMatrix::from_skew_unchecked(m)
+    }
+
+    /// The Pfaffian analog for A: Pf(M) of the real skew form, which is
+    /// always real.
+    ///
+    /// Factoring out i, A = iH with H = -iA Hermitian, and
+    /// Pf(M) = (-1)^(n(n+1)/2) det(H). So Pf(M)² = |det A|², and for even n,
+    /// det(A) = (-1)^(n/2) det(H) is real too. Computed by partial-pivoting
+    /// elimination on M.
+    pub fn pfaffian(&self) -> f64 {
+        self.real_skew_form()
+            .pfaffian_with_pivoting(PivotStrategy::Partial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pfaffian_of_i_times_a_hermitian_2x2_is_minus_its_determinant() {
+        // H = [[2, 1 + i], [1 - i, 3]], det H = 6 - 2 = 4, and n = 2 gives
+        // Pf(M) = (-1)^3 det(H).
+        let c = Complex::new;
+        let h =
+            DMatrix::from_row_slice(2, 2, &[c(2.0, 0.0), c(1.0, 1.0), c(1.0, -1.0), c(3.0, 0.0)]);
+        let a = AntiHermitianMatrix::from_matrix(h * c(0.0, 1.0), 1e-12).unwrap();
+        assert!((a.pfaffian() + 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn real_skew_input_gives_the_square_of_its_pfaffian() {
+        let skew = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let complex = skew.as_matrix().map(|x| Complex::new(x, 0.0));
+        let a = AntiHermitianMatrix::from_matrix(complex, 0.0).unwrap();
+        assert!((a.pfaffian() - 64.0).abs() < 1e-12);
+
+        let symmetric = DMatrix::from_element(2, 2, Complex::new(1.0, 0.0));
+        assert_eq!(
+            AntiHermitianMatrix::from_matrix(symmetric, 1e-12),
+            Err(PfaffianError::NotAntiHermitian { row: 0, col: 0 })
+        );
+    }
+}
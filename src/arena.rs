@@ -0,0 +1,38 @@
+use bumpalo::Bump;
+use std::collections::HashMap;
+
+use crate::SkewMatrix;
+use crate::expansion::{ArenaMemo, PfaffianExpansion, expand_first_row};
+
+impl SkewMatrix {
+    /// The same memoized recursion as `pfaffian()`, but with the memo keys
+    /// allocated from a bump arena.
+    ///
+    /// The expansion keeps its working index lists on one scratch stack, so
+    /// the only per-subproblem allocations left are the keys of the memo
+    /// table, one small Vec each. Here they're bumped out of one arena
+    /// instead, and the table just borrows them, so all of that memory is
+    /// released in one go when the call returns.
+    pub fn pfaffian_arena(&self) -> f64 {
+        let bump = Bump::new();
+        let mut memo = ArenaMemo {
+            bump: &bump,
+            table: HashMap::new(),
+        };
+        let initial_indices: Vec<usize> = (0..self.data.nrows()).collect();
+        let mut expansion = PfaffianExpansion { data: &self.data };
+        expand_first_row(&mut expansion, &initial_indices, &mut memo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SkewMatrix;
+
+    #[test]
+    fn arena_matches_the_default_allocation() {
+        let values: Vec<f64> = (0..91).map(|k| ((k * 5 + 1) as f64).cos()).collect();
+        let matrix = SkewMatrix::from_upper_triangle(14, &values).unwrap();
+        assert_eq!(matrix.pfaffian_arena(), matrix.pfaffian());
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::OnceLock;
+
+use crate::{Ring, SkewMatrix};
+
+/// A `SkewMatrix` that remembers its Pfaffian.
+///
+/// The first `pfaffian()` call runs the recursion and stores the result;
+/// later calls on an unchanged matrix just clone it. `set` forgets it again.
+/// The cache is a `std::sync::OnceLock` rather than a `OnceCell`, so the
+/// wrapper stays `Sync` and can be shared across threads like the matrix.
+pub struct CachedSkewMatrix<T = f64> {
+    matrix: SkewMatrix<T>,
+    pfaffian: OnceLock<T>,
+}
+
+impl<T: Ring> CachedSkewMatrix<T> {
+    /// Wraps a matrix, with nothing cached yet.
+    pub fn new(matrix: SkewMatrix<T>) -> Self {
+        Self {
+            matrix,
+            pfaffian: OnceLock::new(),
+        }
+    }
+
+    /// Borrows the wrapped matrix.
+    pub fn as_skew_matrix(&self) -> &SkewMatrix<T> {
+        &self.matrix
+    }
+
+    /// Unwraps the matrix, dropping the cached Pfaffian.
+    pub fn into_inner(self) -> SkewMatrix<T> {
+        self.matrix
+    }
+
+    /// Like `SkewMatrix::set`, and forgets the cached Pfaffian.
+    pub fn set(&mut self, i: usize, j: usize, value: T) {
+        self.matrix.set(i, j, value);
+        self.pfaffian = OnceLock::new();
+    }
+
+    /// `SkewMatrix::pfaffian()`, computed on the first call and cached after
+    /// that.
+    pub fn pfaffian(&self) -> T {
+        self.pfaffian.get_or_init(|| self.matrix.pfaffian()).clone()
+    }
+
+    /// Like `pfaffian()`, but `clone_from`s the cached value into `out`, so
+    /// `out` reuses its allocation and no new value is built.
+    pub fn pfaffian_into_out(&self, out: &mut T) {
+        out.clone_from(self.pfaffian.get_or_init(|| self.matrix.pfaffian()));
+    }
+}
+
+impl<T: Ring> From<SkewMatrix<T>> for CachedSkewMatrix<T> {
+    fn from(matrix: SkewMatrix<T>) -> Self {
+        Self::new(matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_forgets_the_cached_pfaffian() {
+        let matrix = SkewMatrix::from_upper_triangle(2, &[3.0]).unwrap();
+        let mut cached = CachedSkewMatrix::new(matrix);
+        assert_eq!(cached.pfaffian(), 3.0);
+        assert_eq!(cached.pfaffian(), 3.0);
+
+        cached.set(1, 0, 5.0);
+        assert_eq!(cached.pfaffian(), -5.0);
+        let mut out = 0.0;
+        cached.pfaffian_into_out(&mut out);
+        assert_eq!(out, -5.0);
+        assert_eq!(cached.into_inner().pfaffian(), -5.0);
+    }
+}
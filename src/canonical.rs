@@ -0,0 +1,223 @@
+use nalgebra::DMatrix;
+
+use crate::{PivotStrategy, SkewMatrix};
+
+impl SkewMatrix {
+    /// Finds an orthogonal Q and λ_1, ..., λ_{n/2} ≥ 0 such that QᵀAQ is block
+    /// diagonal with 2x2 blocks [[0, λ_k], [-λ_k, 0]], returned as (Q, λ).
+    ///
+    /// A skew matrix is normal, so its real Schur form is already block
+    /// diagonal; we only need to gather the 2x2 blocks, pair up the zero
+    /// eigenvalues, and flip column pairs so every λ comes out non-negative.
+    /// Since Pf(QᵀAQ) = det(Q) Pf(A) and det(Q) = ±1, this gives
+    /// Pf(A) = det(Q) ∏ λ_k.
+    ///
+    /// The Schur iteration can't converge on NaN or infinite entries, so if
+    /// A has one (or the iteration gives up anyway), every entry of Q and
+    /// every λ comes back NaN.
+    pub fn canonical_form(&self) -> (DMatrix<f64>, Vec<f64>) {
+        let n = self.data.nrows();
+        let failed = || (DMatrix::from_element(n, n, f64::NAN), vec![f64::NAN; n / 2]);
+
+        // Work on A / max|A_ij| so huge or tiny entries can't overflow inside
+        // the iteration, then scale the λ back up at the end.
+        let scale = match self.data.amax() {
+            s if !s.is_finite() => return failed(),
+            0.0 => 1.0,
+            s => s,
+        };
+        let scaled = &self.data / scale;
+        let Some(schur) = scaled.clone().try_schur(f64::EPSILON, 1000 * n.max(1)) else {
+            return failed();
+        };
+        let (q, t) = schur.unpack();
+
+        // Anything this small below the diagonal is a deflated zero, not a block.
+        let tol = f64::EPSILON * (n as f64) * scaled.norm();
+        let mut pairs = Vec::with_capacity(n / 2);
+        let mut zeros = Vec::new();
+        let mut k = 0;
+        while k < n {
+            if k + 1 < n && t[(k + 1, k)].abs() > tol {
+                pairs.push((k, k + 1));
+                k += 2;
+            } else {
+                zeros.push(k);
+                k += 1;
+            }
+        }
+        // n is even and the blocks take two columns each, so the zero
+        // eigenvalues come in an even number too.
+        for z in zeros.chunks(2) {
+            pairs.push((z[0], z[1]));
+        }
+
+        let mut q_out = DMatrix::<f64>::zeros(n, n);
+        let mut lambdas = Vec::with_capacity(n / 2);
+        for (b, &(i, j)) in pairs.iter().enumerate() {
+            let (i, j) = if t[(i, j)] < 0.0 { (j, i) } else { (i, j) };
+            q_out.set_column(2 * b, &q.column(i));
+            q_out.set_column(2 * b + 1, &q.column(j));
+            lambdas.push(t[(i, j)].abs() * scale);
+        }
+        (q_out, lambdas)
+    }
+
+    /// A regularized Pfaffian that treats every canonical λ_k below
+    /// `threshold` as an exact zero mode, returned as (number of modes
+    /// dropped, det(Q) ∏ of the remaining λ_k).
+    ///
+    /// The λ_k are the singular values of A (each appears twice), so this
+    /// is the Pfaffian of A restricted to the span of its well-conditioned
+    /// 2x2 blocks. The magnitude doesn't move as a dropped λ wanders around
+    /// near zero, where Pf(A) itself is mostly rounding noise. The sign is
+    /// the one Pf(A) would have if every dropped λ were positive, so it can
+    /// still flip when a dropped mode crosses zero; with nothing dropped,
+    /// this is just Pf(A). `threshold` is absolute, in the units of the
+    /// entries.
+    pub fn pfaffian_deflated(&self, threshold: f64) -> (usize, f64) {
+        let (q, lambdas) = self.canonical_form();
+        let sign = if q.determinant() < 0.0 { -1.0 } else { 1.0 };
+        let (dropped, kept): (Vec<f64>, Vec<f64>) =
+            lambdas.into_iter().partition(|&l| l < threshold);
+        (dropped.len(), sign * kept.iter().product::<f64>())
+    }
+
+    /// The inverse of `canonical_form`: builds QCQᵀ, where C is block diagonal
+    /// with 2x2 blocks [[0, λ_k], [-λ_k, 0]]. With no Q this is C itself.
+    ///
+    /// Pf(QCQᵀ) = det(Q) ∏ λ_k for any Q, so this is an easy way to make test
+    /// matrices with a known Pfaffian; an orthogonal Q also keeps the λ as the
+    /// canonical form. Panics if Q isn't 2 * lambdas.len() square.
+    pub fn from_canonical_lambdas(lambdas: &[f64], q: Option<&DMatrix<f64>>) -> SkewMatrix {
+        let n = 2 * lambdas.len();
+        let mut c = DMatrix::<f64>::zeros(n, n);
+        for (k, &lambda) in lambdas.iter().enumerate() {
+            c[(2 * k, 2 * k + 1)] = lambda;
+            c[(2 * k + 1, 2 * k)] = -lambda;
+        }
+
+        let data = match q {
+            Some(q) => {
+                assert_eq!(
+                    q.shape(),
+                    (n, n),
+                    "Q must be {}x{} for {} lambdas.",
+                    n,
+                    n,
+                    lambdas.len()
+                );
+                // Take the exact antisymmetric part, as in `from_matrix`.
+                let m = q * c * q.transpose();
+                (&m - m.transpose()) * 0.5
+            }
+            None => c,
+        };
+        SkewMatrix::from_skew_unchecked(data)
+    }
+}
+
+/// Builds the generator G of the rotation in SO(2m) that turns plane k (the
+/// coordinates 2k, 2k+1) by `angles[k]`, and returns Pf(G).
+///
+/// G is block diagonal with blocks [[0, θ_k], [-θ_k, 0]], and exp(G) is the
+/// rotation itself, since each block exponentiates to [[cos θ_k, sin θ_k],
+/// [-sin θ_k, cos θ_k]]. G is already in canonical form, so Pf(G) = ∏ θ_k:
+/// the Pfaffian of a generator is the product of its rotation angles. For a
+/// generator in any other basis, QGQᵀ with Q in SO(2m) has the same
+/// Pfaffian, and the angles are the λ of `canonical_form`. This builds the
+/// matrix and runs the elimination, rather than just multiplying the
+/// angles, to show the two agree.
+pub fn rotation_generator_pfaffian(angles: &[f64]) -> f64 {
+    SkewMatrix::from_canonical_lambdas(angles, None).pfaffian_with_pivoting(PivotStrategy::Partial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> SkewMatrix {
+        let values = [
+            1.0, -2.0, 0.5, 4.0, 3.0, -1.5, 2.0, 0.25, -3.0, 1.0, 2.5, -0.75, 1.25, -2.0, 0.5,
+        ];
+        SkewMatrix::from_upper_triangle(6, &values).unwrap()
+    }
+
+    #[test]
+    fn canonical_form_reconstructs_the_blocks_and_the_pfaffian() {
+        let matrix = example();
+        let (q, lambdas) = matrix.canonical_form();
+        assert_eq!(lambdas.len(), 3);
+        assert!(lambdas.iter().all(|&l| l >= 0.0));
+
+        let identity = DMatrix::<f64>::identity(6, 6);
+        assert!((q.transpose() * &q - identity).amax() < 1e-12);
+        let blocks = SkewMatrix::from_canonical_lambdas(&lambdas, None);
+        let reduced = q.transpose() * matrix.as_matrix() * &q;
+        assert!((reduced - blocks.as_matrix()).amax() < 1e-12);
+
+        let pf = matrix.pfaffian();
+        let product: f64 = lambdas.iter().product();
+        assert!((product - pf.abs()).abs() < 1e-10 * pf.abs());
+        assert!((q.determinant() * product - pf).abs() < 1e-10 * pf.abs());
+    }
+
+    #[test]
+    fn matrices_built_from_lambdas_have_pfaffian_plus_or_minus_their_product() {
+        let lambdas = [0.5, 2.0, 3.0];
+        let product: f64 = lambdas.iter().product();
+        let plain = SkewMatrix::from_canonical_lambdas(&lambdas, None);
+        assert_eq!(plain.pfaffian(), product);
+
+        let q = DMatrix::from_fn(6, 6, |i, j| ((i * 5 + j * 3) % 7) as f64 - 3.0)
+            .qr()
+            .q();
+        let rotated = SkewMatrix::from_canonical_lambdas(&lambdas, Some(&q));
+        let expected = q.determinant().signum() * product;
+        assert!((rotated.pfaffian() - expected).abs() < 1e-12);
+
+        let mut recovered = rotated.canonical_form().1;
+        recovered.sort_by(f64::total_cmp);
+        for (got, want) in recovered.iter().zip(lambdas) {
+            assert!((got - want).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn rotation_generator_pfaffian_is_the_product_of_the_angles() {
+        for angles in [
+            vec![],
+            vec![0.3],
+            vec![0.3, -1.2],
+            vec![std::f64::consts::FRAC_PI_2, 0.7, -2.5, 0.0],
+        ] {
+            let product: f64 = angles.iter().product();
+            let pf = rotation_generator_pfaffian(&angles);
+            assert!(
+                (pf - product).abs() < 1e-15,
+                "{:?}: {} vs {}",
+                angles,
+                pf,
+                product
+            );
+        }
+    }
+
+    #[test]
+    fn deflating_a_near_zero_mode_gives_a_stable_value() {
+        let q = DMatrix::from_fn(6, 6, |i, j| ((i * 3 + j * 5) % 7) as f64 - 3.0)
+            .qr()
+            .q();
+        let sign = q.determinant().signum();
+        for tiny in [1e-14, 3e-13, 1e-11] {
+            let matrix = SkewMatrix::from_canonical_lambdas(&[2.0, tiny, 3.0], Some(&q));
+            let (dropped, pf) = matrix.pfaffian_deflated(1e-8);
+            assert_eq!(dropped, 1);
+            assert!((pf - sign * 6.0).abs() < 1e-12, "λ = {}: {}", tiny, pf);
+
+            let (dropped, pf) = matrix.pfaffian_deflated(0.0);
+            assert_eq!(dropped, 0);
+            assert!((pf - matrix.pfaffian_with_pivoting(PivotStrategy::Partial)).abs() < 1e-12);
+        }
+    }
+}
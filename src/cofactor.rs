@@ -0,0 +1,72 @@
+use crate::{PivotStrategy, SkewMatrix};
+
+impl SkewMatrix {
+    /// A with rows and columns i and j both removed, for i != j.
+    pub(crate) fn without_pair(&self, i: usize, j: usize) -> SkewMatrix {
+        let (lo, hi) = if i < j { (i, j) } else { (j, i) };
+        SkewMatrix::from_skew_unchecked(
+            self.data
+                .clone()
+                .remove_row(hi)
+                .remove_row(lo)
+                .remove_column(hi)
+                .remove_column(lo),
+        )
+    }
+
+    /// The sub-Pfaffians Pf(A with rows/cols i and j removed) for every
+    /// partner j, indexed by j. Entry i itself has no partner and is 0.
+    ///
+    /// These are the terms of the expansion along row i,
+    ///
+    ///    Pf(A) = ∑_{j ≠ i} s_ij · A[i][j] · result[j]
+    ///
+    /// with s_ij = (-1)^(i+j+1) for j > i and (-1)^(i+j) for j < i. For i = 0
+    /// this is exactly the recursion behind `pfaffian()`. Each sub-Pfaffian
+    /// is one elimination, so this costs O(n^4). Panics if i is out of range.
+    pub fn cofactor_pfaffians_for(&self, i: usize) -> Vec<f64> {
+        let n = self.data.nrows();
+        assert!(
+            i < n,
+            "Index {} is out of range for an {}x{} matrix.",
+            i,
+            n,
+            n
+        );
+        (0..n)
+            .map(|j| {
+                if j == i {
+                    0.0
+                } else {
+                    self.without_pair(i, j)
+                        .pfaffian_with_pivoting(PivotStrategy::Partial)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recombining_the_cofactors_along_any_row_gives_the_pfaffian() {
+        let values: Vec<f64> = (1..=15).map(|k| ((k * 4) % 9) as f64 - 3.5).collect();
+        let matrix = SkewMatrix::from_upper_triangle(6, &values).unwrap();
+        let expected = matrix.pfaffian();
+        for i in 0..6 {
+            let cofactors = matrix.cofactor_pfaffians_for(i);
+            assert_eq!(cofactors[i], 0.0);
+            let total: f64 = (0..6)
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let exponent = if j > i { i + j + 1 } else { i + j };
+                    let sign = if exponent % 2 == 1 { -1.0 } else { 1.0 };
+                    sign * matrix.as_matrix()[(i, j)] * cofactors[j]
+                })
+                .sum();
+            assert!((total - expected).abs() < 1e-9, "row {}", i);
+        }
+    }
+}
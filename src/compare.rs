@@ -0,0 +1,90 @@
+use crate::SkewMatrix;
+
+impl SkewMatrix {
+    /// The largest |A_ij - B_ij| over all entries, or infinity if the two
+    /// matrices have different sizes. A NaN on either side makes the result
+    /// NaN, as it counts as a mismatch in `assert_skew_matrices_close`.
+    pub fn max_abs_diff(&self, other: &SkewMatrix) -> f64 {
+        if self.data.shape() != other.data.shape() {
+            return f64::INFINITY;
+        }
+        self.data
+            .iter()
+            .zip(other.data.iter())
+            .fold(0.0, |max, (a, b)| {
+                let d = (a - b).abs();
+                if d > max || d.is_nan() { d } else { max }
+            })
+    }
+}
+
+/// Panics unless `a` and `b` have the same size and every entry agrees to
+/// within `tol`. The message names the first offending entry (scanning the
+/// upper triangle row by row, since the lower one is just its mirror) with
+/// both values.
+#[track_caller]
+pub fn assert_skew_matrices_close(a: &SkewMatrix, b: &SkewMatrix, tol: f64) {
+    let n = a.data.nrows();
+    assert!(
+        a.data.shape() == b.data.shape(),
+        "skew matrices differ in size: {}x{} vs {}x{}",
+        n,
+        n,
+        b.data.nrows(),
+        b.data.nrows()
+    );
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let (x, y) = (a.data[(i, j)], b.data[(i, j)]);
+            // A NaN on either side counts as a mismatch.
+            let diff = (x - y).abs();
+            if diff > tol || diff.is_nan() {
+                panic!(
+                    "skew matrices differ at ({}, {}): {} vs {} (|diff| = {}, tol = {})",
+                    i, j, x, y, diff, tol
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: [f64; 6] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+
+    #[test]
+    fn near_match_passes() {
+        let a = SkewMatrix::from_upper_triangle(4, &VALUES).unwrap();
+        let nudged: Vec<f64> = VALUES.iter().map(|v| v + 1e-10).collect();
+        let b = SkewMatrix::from_upper_triangle(4, &nudged).unwrap();
+        assert!(a.max_abs_diff(&b) < 2e-10);
+        assert_skew_matrices_close(&a, &b, 1e-9);
+        assert_eq!(a.max_abs_diff(&SkewMatrix::empty()), f64::INFINITY);
+    }
+
+    #[test]
+    #[should_panic(expected = "skew matrices differ at (1, 3): 5 vs 5.5")]
+    fn mismatch_names_the_first_bad_entry() {
+        let a = SkewMatrix::from_upper_triangle(4, &VALUES).unwrap();
+        let mut changed = VALUES;
+        changed[4] = 5.5;
+        changed[5] = 7.0;
+        let b = SkewMatrix::from_upper_triangle(4, &changed).unwrap();
+        assert_eq!(a.max_abs_diff(&b), 1.0);
+        assert_skew_matrices_close(&a, &b, 1e-9);
+    }
+
+    #[test]
+    fn nan_entries_propagate_into_the_max_diff() {
+        let a = SkewMatrix::from_upper_triangle(4, &VALUES).unwrap();
+        let mut with_nan = VALUES;
+        with_nan[2] = f64::NAN;
+        let b = SkewMatrix::from_upper_triangle_allow_non_finite(4, &with_nan).unwrap();
+        assert!(a.max_abs_diff(&b).is_nan());
+        assert!(b.max_abs_diff(&a).is_nan());
+        let all_nan = SkewMatrix::from_upper_triangle_allow_non_finite(4, &[f64::NAN; 6]).unwrap();
+        assert!(all_nan.max_abs_diff(&all_nan).is_nan());
+    }
+}
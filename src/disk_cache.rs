@@ -0,0 +1,157 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::{PfaffianError, PivotStrategy, SkewMatrix};
+
+/// A directory of previously computed Pfaffians that survives restarts.
+///
+/// Matrices are keyed by a hash of n and the exact bits of their upper
+/// triangle. Each hash gets its own file of "key = value" lines, and the
+/// full key is stored and compared on lookup, so two matrices whose hashes
+/// collide just share a file. Values are written as raw f64 bit patterns
+/// so a cached result comes back bit-for-bit identical.
+///
+/// Writes are appends of whole lines, which is fine for one process at a
+/// time; there's no locking between processes.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+/// FNV-1a. std's hasher isn't guaranteed stable across Rust releases,
+/// which matters for something written to disk.
+fn fnv1a(words: &[u64]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for word in words {
+        for byte in word.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+impl DiskCache {
+    /// Opens (creating it if needed) a cache in the directory `dir`.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, PfaffianError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| PfaffianError::Io {
+            path: dir.clone(),
+            message: e.to_string(),
+        })?;
+        Ok(Self { dir })
+    }
+
+    /// n followed by the bits of each upper-triangle entry.
+    fn key(m: &SkewMatrix) -> Vec<u64> {
+        let n = m.data.nrows();
+        let mut key = Vec::with_capacity(1 + n * n.saturating_sub(1) / 2);
+        key.push(n as u64);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                key.push(m.data[(i, j)].to_bits());
+            }
+        }
+        key
+    }
+
+    fn bucket(&self, key: &[u64]) -> PathBuf {
+        self.dir.join(format!("{:016x}.pf", fnv1a(key)))
+    }
+
+    fn encode(key: &[u64]) -> String {
+        key.iter()
+            .map(|w| format!("{:016x}", w))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The stored Pfaffian of `m`, if there is one. A missing or unreadable
+    /// bucket file counts as a miss.
+    pub fn lookup(&self, m: &SkewMatrix) -> Option<f64> {
+        let key = Self::key(m);
+        let encoded = Self::encode(&key);
+        let text = fs::read_to_string(self.bucket(&key)).ok()?;
+        text.lines().find_map(|line| {
+            let (stored_key, value) = line.split_once(" = ")?;
+            if stored_key != encoded {
+                return None;
+            }
+            u64::from_str_radix(value, 16).ok().map(f64::from_bits)
+        })
+    }
+
+    /// Records Pf(m) = value.
+    pub fn insert(&self, m: &SkewMatrix, value: f64) -> Result<(), PfaffianError> {
+        let key = Self::key(m);
+        let path = self.bucket(&key);
+        let io_error = |e: std::io::Error| PfaffianError::Io {
+            path: path.clone(),
+            message: e.to_string(),
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(io_error)?;
+        writeln!(file, "{} = {:016x}", Self::encode(&key), value.to_bits()).map_err(io_error)
+    }
+}
+
+impl SkewMatrix {
+    /// Looks the Pfaffian up in `cache`, computing it by partial-pivoting
+    /// elimination (and storing it) on a miss.
+    ///
+    /// The cache is best-effort: if storing the result fails, the computed
+    /// value is still returned and the next call just recomputes it.
+    pub fn pfaffian_cached(&self, cache: &DiskCache) -> f64 {
+        if let Some(value) = cache.lookup(self) {
+            return value;
+        }
+        let value = self.pfaffian_with_pivoting(PivotStrategy::Partial);
+        let _ = cache.insert(self, value);
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_call_hits_the_cache_with_identical_bits() {
+        let dir = std::env::temp_dir().join(format!("pfaffian-disk-cache-{}", std::process::id()));
+        let cache = DiskCache::open(&dir).unwrap();
+        let values: Vec<f64> = (0..15).map(|k| ((k * 3 + 1) as f64).sin()).collect();
+        let matrix = SkewMatrix::from_upper_triangle(6, &values).unwrap();
+
+        assert_eq!(cache.lookup(&matrix), None);
+        let first = matrix.pfaffian_cached(&cache);
+        assert_eq!(
+            cache.lookup(&matrix).map(f64::to_bits),
+            Some(first.to_bits())
+        );
+
+        let second = matrix.pfaffian_cached(&cache);
+        assert_eq!(second.to_bits(), first.to_bits());
+
+        // The second call read the file rather than recomputing: a stored
+        // value it could never compute comes back as is, past an entry for
+        // another key in the same bucket.
+        let key = DiskCache::key(&matrix);
+        fs::write(
+            cache.bucket(&key),
+            format!(
+                "0000000000000002 = {:016x}\n{} = {:016x}\n",
+                1f64.to_bits(),
+                DiskCache::encode(&key),
+                42f64.to_bits()
+            ),
+        )
+        .unwrap();
+        assert_eq!(matrix.pfaffian_cached(&cache), 42.0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,216 @@
+use nalgebra::DMatrix;
+
+use crate::{PfaffianError, SkewMatrix};
+
+/// One worker's share of a Pfaffian over a block-arrowhead partition.
+///
+/// The matrix is split into interior blocks A_1, ..., A_k, which only couple
+/// to a trailing separator block S, through B_1, ..., B_k:
+///
+///    A_1              B_1
+///         ...         ...
+///              A_k    B_k
+///   -B_1ᵀ ... -B_kᵀ   A_S
+///
+/// with the rows/cols in that order. Eliminating the interior blocks gives
+/// Pf = ∏ Pf(A_i) · Pf(A_S + ∑ B_iᵀ A_i⁻¹ B_i), and the terms of the product
+/// and the sum are independent, so worker i only needs A_i and B_i. Its chunk
+/// is Pf(A_i), kept as a sign and log-magnitude, and the s x s Schur
+/// contribution B_iᵀ A_i⁻¹ B_i. The separator itself is one more chunk, with
+/// Pf = 1 and contribution A_S. A plain 2-way split [[A_11, A_12], [-A_12ᵀ,
+/// A_22]] is the case k = 1.
+///
+/// Chunks go to and from a line-based text form with `to_text` and
+/// `from_text`. Numbers are written as raw f64 bit patterns, as in
+/// `DiskCache`, so a chunk comes back bit-for-bit identical.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialFactorization {
+    sign: f64,
+    log_abs: f64,
+    schur: DMatrix<f64>,
+}
+
+impl PartialFactorization {
+    /// A worker's chunk for the interior block A_i and its coupling B_i to
+    /// the separator (one row per row of A_i, one column per separator row).
+    /// None if A_i is singular, since its inverse is needed; a different
+    /// partition is then required.
+    /// Panics if B_i doesn't have one row per row of A_i.
+    pub fn interior(block: &SkewMatrix, coupling: &DMatrix<f64>) -> Option<Self> {
+        assert_eq!(
+            coupling.nrows(),
+            block.data.nrows(),
+            "The coupling must have one row per row of the block."
+        );
+        let (sign, log_abs) = block.log_pfaffian();
+        if sign == 0.0 {
+            return None;
+        }
+        let solved = block.data.clone().lu().solve(coupling)?;
+        let schur = coupling.transpose() * solved;
+        Some(Self {
+            sign,
+            log_abs,
+            // Bᵀ A⁻¹ B is skew, since A⁻¹ is; drop the rounding.
+            schur: (&schur - schur.transpose()) * 0.5,
+        })
+    }
+
+    /// The chunk for the separator block A_S itself.
+    pub fn separator(block: &SkewMatrix) -> Self {
+        Self {
+            sign: 1.0,
+            log_abs: 0.0,
+            schur: block.data.clone(),
+        }
+    }
+
+    /// Assembles the Pfaffian of the whole partitioned matrix from every
+    /// chunk, the separator's included, in any order.
+    /// With no chunks at all this is the empty Pfaffian, 1.
+    /// Panics if the chunks disagree on the separator size.
+    pub fn combine(chunks: &[PartialFactorization]) -> f64 {
+        let Some(first) = chunks.first() else {
+            return 1.0;
+        };
+        let s = first.schur.nrows();
+        let mut reduced = DMatrix::<f64>::zeros(s, s);
+        let mut sign = 1.0;
+        let mut log_abs = 0.0;
+        for chunk in chunks {
+            assert_eq!(
+                chunk.schur.nrows(),
+                s,
+                "All chunks must share the same separator size."
+            );
+            reduced += &chunk.schur;
+            sign *= chunk.sign;
+            log_abs += chunk.log_abs;
+        }
+        let (reduced_sign, reduced_log) = SkewMatrix::from_skew_unchecked(reduced).log_pfaffian();
+        sign * reduced_sign * (log_abs + reduced_log).exp()
+    }
+
+    /// Writes the chunk as text: a line with the sign and log-magnitude, a
+    /// line with the separator size s, then s lines of the contribution.
+    pub fn to_text(&self) -> String {
+        let s = self.schur.nrows();
+        let mut out = format!(
+            "{:016x} {:016x}\n{}\n",
+            self.sign.to_bits(),
+            self.log_abs.to_bits(),
+            s
+        );
+        for i in 0..s {
+            let row: Vec<String> = (0..s)
+                .map(|j| format!("{:016x}", self.schur[(i, j)].to_bits()))
+                .collect();
+            out.push_str(&row.join(" "));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Reads a chunk written by `to_text`.
+    pub fn from_text(text: &str) -> Result<Self, PfaffianError> {
+        let lines: Vec<&str> = text.lines().collect();
+        // Line numbers in errors are 1-based, as in `read_matrix`.
+        let line = |k: usize| {
+            lines.get(k).copied().ok_or(PfaffianError::Parse {
+                line: k + 1,
+                message: "unexpected end of chunk".to_string(),
+            })
+        };
+
+        let header = parse_words(1, line(0)?)?;
+        let [sign, log_abs] = header[..] else {
+            return Err(PfaffianError::Parse {
+                line: 1,
+                message: format!("expected 2 values, got {}", header.len()),
+            });
+        };
+
+        let size = line(1)?.trim();
+        let s: usize = size.parse().map_err(|_| PfaffianError::Parse {
+            line: 2,
+            message: format!("invalid size {:?}", size),
+        })?;
+
+        let mut schur = DMatrix::<f64>::zeros(s, s);
+        for i in 0..s {
+            let row = parse_words(i + 3, line(i + 2)?)?;
+            if row.len() != s {
+                return Err(PfaffianError::Parse {
+                    line: i + 3,
+                    message: format!("expected {} values, got {}", s, row.len()),
+                });
+            }
+            for (j, value) in row.into_iter().enumerate() {
+                schur[(i, j)] = value;
+            }
+        }
+        Ok(Self {
+            sign,
+            log_abs,
+            schur,
+        })
+    }
+}
+
+/// Parses a line of f64 bit patterns in hex.
+fn parse_words(line: usize, text: &str) -> Result<Vec<f64>, PfaffianError> {
+    text.split_whitespace()
+        .map(|word| {
+            u64::from_str_radix(word, 16)
+                .map(f64::from_bits)
+                .map_err(|_| PfaffianError::Parse {
+                    line,
+                    message: format!("invalid value {:?}", word),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PivotStrategy;
+
+    fn block(data: &DMatrix<f64>, start: usize, len: usize) -> SkewMatrix {
+        SkewMatrix::from_skew_unchecked(data.view((start, start), (len, len)).into_owned())
+    }
+
+    #[test]
+    fn two_way_partition_combines_to_the_single_node_pfaffian() {
+        let values: Vec<f64> = (0..45).map(|k| ((k * 11 + 3) as f64).cos()).collect();
+        let matrix = SkewMatrix::from_upper_triangle(10, &values).unwrap();
+        let a = matrix.as_matrix();
+        let expected = matrix.pfaffian_with_pivoting(PivotStrategy::Partial);
+
+        let worker =
+            PartialFactorization::interior(&block(a, 0, 6), &a.view((0, 6), (6, 4)).into_owned())
+                .unwrap();
+        let separator = PartialFactorization::separator(&block(a, 6, 4));
+
+        // Each chunk crosses the wire as text and comes back bit for bit.
+        let chunks: Vec<PartialFactorization> = [worker, separator]
+            .iter()
+            .map(|chunk| {
+                let back = PartialFactorization::from_text(&chunk.to_text()).unwrap();
+                assert_eq!(&back, chunk);
+                back
+            })
+            .collect();
+        let pf = PartialFactorization::combine(&chunks);
+        assert!(
+            (pf - expected).abs() < 1e-10 * expected.abs(),
+            "{} vs {}",
+            pf,
+            expected
+        );
+
+        let reversed = [chunks[1].clone(), chunks[0].clone()];
+        assert!((PartialFactorization::combine(&reversed) - pf).abs() < 1e-12 * pf.abs());
+        assert!(PartialFactorization::from_text("0 0\n2\n0 0\n").is_err());
+    }
+}
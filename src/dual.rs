@@ -0,0 +1,141 @@
+use nalgebra::Complex;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_traits::{One, Zero};
+
+/// A complex dual number a + b ε with ε² = 0, for forward-mode
+/// differentiation of complex functions of one real parameter θ.
+///
+/// Seed each entry with its value and its derivative dA_ij/dθ; then the
+/// `value()` of a result is the result and its `deriv()` is d/dθ of it. This
+/// is a `Ring`, so `SkewMatrix<ComplexDual>::pfaffian()` gives Pf(A) and
+/// dPf/dθ in one pass of the division-free recursion.
+///
+/// For entries that depend holomorphically on a complex z (rather than on a
+/// real θ), seeding with dA/dz gives the Wirtinger derivative ∂Pf/∂z, since
+/// the Pfaffian is a polynomial in the entries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplexDual {
+    value: Complex<f64>,
+    deriv: Complex<f64>,
+}
+
+impl ComplexDual {
+    pub fn new(value: Complex<f64>, deriv: Complex<f64>) -> Self {
+        Self { value, deriv }
+    }
+
+    /// A value that doesn't depend on θ.
+    pub fn constant(value: Complex<f64>) -> Self {
+        Self::new(value, Complex::zero())
+    }
+
+    pub fn value(&self) -> Complex<f64> {
+        self.value
+    }
+
+    pub fn deriv(&self) -> Complex<f64> {
+        self.deriv
+    }
+}
+
+impl Add for ComplexDual {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.value + rhs.value, self.deriv + rhs.deriv)
+    }
+}
+
+impl Sub for ComplexDual {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.value - rhs.value, self.deriv - rhs.deriv)
+    }
+}
+
+impl Neg for ComplexDual {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.value, -self.deriv)
+    }
+}
+
+impl Mul for ComplexDual {
+    type Output = Self;
+
+    // (a + b ε)(c + d ε) = ac + (ad + bc) ε
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.value * rhs.value,
+            self.value * rhs.deriv + self.deriv * rhs.value,
+        )
+    }
+}
+
+impl Zero for ComplexDual {
+    fn zero() -> Self {
+        Self::constant(Complex::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value.is_zero() && self.deriv.is_zero()
+    }
+}
+
+impl One for ComplexDual {
+    fn one() -> Self {
+        Self::constant(Complex::one())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkewMatrix;
+
+    /// The upper triangle of a 4x4 complex matrix depending on θ, with its
+    /// entrywise derivative.
+    fn entries(theta: f64) -> [(Complex<f64>, Complex<f64>); 6] {
+        let i = Complex::i();
+        let phase = (i * theta).exp();
+        [
+            (phase, i * phase),
+            (Complex::new(2.0, -1.0), Complex::zero()),
+            (
+                Complex::new(theta * theta, 1.0),
+                Complex::new(2.0 * theta, 0.0),
+            ),
+            (Complex::new(0.5, theta), i),
+            (Complex::new(3.0, 0.0), Complex::zero()),
+            (phase * theta, phase + i * phase * theta),
+        ]
+    }
+
+    fn pfaffian_at(theta: f64) -> ComplexDual {
+        let values = entries(theta).map(|(v, d)| ComplexDual::new(v, d));
+        SkewMatrix::from_upper_triangle_generic(4, &values)
+            .unwrap()
+            .pfaffian()
+    }
+
+    #[test]
+    fn dual_pfaffian_derivative_matches_finite_differences() {
+        let (theta, h) = (0.7, 1e-6);
+        let pf = pfaffian_at(theta);
+        let plain = SkewMatrix::from_upper_triangle_generic(4, &entries(theta).map(|(v, _)| v))
+            .unwrap()
+            .pfaffian();
+        assert!((pf.value() - plain).norm() < 1e-12);
+
+        let numeric = (pfaffian_at(theta + h).value() - pfaffian_at(theta - h).value()) / (2.0 * h);
+        assert!(
+            (pf.deriv() - numeric).norm() < 1e-7,
+            "{} vs {}",
+            pf.deriv(),
+            numeric
+        );
+    }
+}
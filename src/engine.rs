@@ -0,0 +1,139 @@
+use nalgebra::DMatrix;
+use std::sync::Mutex;
+
+use crate::elimination::eliminate_in_place;
+use crate::{PivotStrategy, SkewMatrix};
+
+/// Scratch space for one elimination: the working copy of A and its pivots.
+struct Workspace {
+    a: DMatrix<f64>,
+    pivots: Vec<f64>,
+}
+
+/// A configured Pfaffian evaluator that can be shared between threads,
+/// e.g. behind an `Arc`.
+///
+/// It holds the pivoting strategy and zero tolerance, so call sites don't
+/// each have to pick their own, plus a pool of elimination workspaces. Each
+/// call borrows a workspace from the pool for the duration of the
+/// elimination and returns it afterwards, so concurrent callers never share
+/// scratch memory and the pool grows to one workspace per thread that is
+/// actually using the engine at once.
+pub struct PfaffianEngine {
+    strategy: PivotStrategy,
+    zero_tolerance: f64,
+    pool: Mutex<Vec<Workspace>>,
+}
+
+impl PfaffianEngine {
+    /// An engine using partial pivoting and an exact zero test.
+    pub fn new() -> Self {
+        Self {
+            strategy: PivotStrategy::Partial,
+            zero_tolerance: 0.0,
+            pool: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Sets the pivoting strategy used by the elimination.
+    pub fn strategy(mut self, strategy: PivotStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Treats the matrix as singular (Pfaffian 0) if some pivot is at most
+    /// `tol` times the largest entry of A in absolute value.
+    pub fn zero_tolerance(mut self, tol: f64) -> Self {
+        self.zero_tolerance = tol;
+        self
+    }
+
+    /// Computes Pf(A) by skew elimination with this engine's settings.
+    pub fn pfaffian(&self, matrix: &SkewMatrix) -> f64 {
+        // A panic in another thread can't leave a workspace half-updated in
+        // a way that matters, since each call overwrites it, so a poisoned
+        // pool is still fine to use.
+        let taken = self
+            .pool
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop();
+        let mut ws = match taken {
+            Some(mut ws) if ws.a.shape() == matrix.data.shape() => {
+                ws.a.copy_from(&matrix.data);
+                ws
+            }
+            Some(mut ws) => {
+                ws.a = matrix.data.clone();
+                ws
+            }
+            None => Workspace {
+                a: matrix.data.clone(),
+                pivots: Vec::new(),
+            },
+        };
+
+        let sign = eliminate_in_place(&mut ws.a, self.strategy, &mut ws.pivots);
+        let threshold = self.zero_tolerance * matrix.data.amax();
+        let result = if ws.pivots.iter().any(|p| p.abs() <= threshold) {
+            0.0
+        } else {
+            sign * ws.pivots.iter().product::<f64>()
+        };
+
+        self.pool
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(ws);
+        result
+    }
+}
+
+impl Default for PfaffianEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn example(n: usize, seed: usize) -> SkewMatrix {
+        let values: Vec<f64> = (0..n * (n - 1) / 2)
+            .map(|k| ((k * 7 + seed * 13) % 17) as f64 / 4.0 - 2.0)
+            .collect();
+        SkewMatrix::from_upper_triangle(n, &values).unwrap()
+    }
+
+    #[test]
+    fn threads_sharing_one_engine_get_the_direct_results() {
+        let engine = Arc::new(PfaffianEngine::new().strategy(PivotStrategy::Rook));
+        let handles: Vec<_> = (0..8)
+            .map(|seed| {
+                let engine = Arc::clone(&engine);
+                thread::spawn(move || {
+                    for round in 0..50 {
+                        // Vary the size so pooled workspaces get reshaped.
+                        let m = example(2 + 2 * ((seed + round) % 5), seed + round);
+                        let expected = m.pfaffian_with_pivoting(PivotStrategy::Rook);
+                        assert_eq!(engine.pfaffian(&m), expected);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(engine.pool.lock().unwrap().len() <= 8);
+    }
+
+    #[test]
+    fn zero_tolerance_flags_a_tiny_pivot_as_singular() {
+        let m = SkewMatrix::from_upper_triangle(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 1e-12]).unwrap();
+        assert_eq!(PfaffianEngine::new().pfaffian(&m), 1e-12);
+        assert_eq!(PfaffianEngine::new().zero_tolerance(1e-9).pfaffian(&m), 0.0);
+    }
+}
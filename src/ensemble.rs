@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use crate::{PivotStrategy, SkewMatrix};
+
+/// The n with n(n-1)/2 = len, for an even n. Panics if there isn't one.
+fn upper_triangle_size(len: usize) -> usize {
+    let mut n: usize = 0;
+    while n * n.saturating_sub(1) / 2 < len {
+        n += 1;
+    }
+    assert!(
+        n * n.saturating_sub(1) / 2 == len && n.is_multiple_of(2),
+        "{} values is not the upper triangle of an even-sized matrix.",
+        len
+    );
+    n
+}
+
+/// E[Pf(A)] when each upper-triangle entry A_ij is an independent Gaussian
+/// with mean `means[k]` and variance `variances[k]`, both laid out as in
+/// `SkewMatrix::from_upper_triangle`.
+///
+/// Every matching uses each entry at most once, so by independence the
+/// expectation of each term is the product of the means, and E[Pf(A)] is
+/// just Pf(E[A]). The variances don't enter at all. They do for E[Pf²],
+/// which is not Pf(E[A])² (see `expected_pfaffian_squared`).
+/// Panics if the slices have different lengths or aren't an upper triangle.
+pub fn expected_pfaffian(means: &[f64], variances: &[f64]) -> f64 {
+    assert_eq!(
+        means.len(),
+        variances.len(),
+        "Need one mean and one variance per entry."
+    );
+    let n = upper_triangle_size(means.len());
+    match SkewMatrix::from_upper_triangle_allow_non_finite(n, means) {
+        Ok(mean) => mean.pfaffian_with_pivoting(PivotStrategy::Partial),
+        Err(_) => unreachable!("upper_triangle_size checked the length"),
+    }
+}
+
+/// E[Pf(A)²] = E[det(A)] for the same Gaussian ensemble as
+/// `expected_pfaffian`.
+///
+/// Pf² = det(A) is quadratic in each entry, so E[A_ij²] = μ_ij² + σ_ij²
+/// shows up wherever both copies of Pf use the same edge. We expand both
+/// Pfaffians along their first rows at once: F(S, T) = E[Pf(A_S) Pf(A_T)]
+/// splits into terms where the edges at the smallest vertex are
+/// independent of everything left, giving a recursion over pairs of vertex
+/// subsets. With zero means this is the Hafnian of the variances, which is
+/// #P-hard, so the cost is exponential in n and this is for small n only.
+/// Panics like `expected_pfaffian`, or if n > 64.
+pub fn expected_pfaffian_squared(means: &[f64], variances: &[f64]) -> f64 {
+    assert_eq!(
+        means.len(),
+        variances.len(),
+        "Need one mean and one variance per entry."
+    );
+    let n = upper_triangle_size(means.len());
+    assert!(n <= 64, "expected_pfaffian_squared only supports n <= 64.");
+    let index = |i: usize, j: usize| i * n - i * (i + 1) / 2 + (j - i - 1);
+    let ensemble = Ensemble {
+        mean: &|i, j| means[index(i, j)],
+        second_moment: &|i, j| means[index(i, j)].powi(2) + variances[index(i, j)],
+    };
+    let mut memo = HashMap::new();
+    let all = if n == 64 { u64::MAX } else { (1u64 << n) - 1 };
+    ensemble.cross_moment(all, all, &mut memo)
+}
+
+/// The first and second moments of the entries, indexed by (i, j) with i < j.
+struct Ensemble<'a> {
+    mean: &'a dyn Fn(usize, usize) -> f64,
+    second_moment: &'a dyn Fn(usize, usize) -> f64,
+}
+
+impl Ensemble<'_> {
+    /// F(S, T) = E[Pf(A_S) Pf(A_T)] for the vertex sets S and T (as bitmasks).
+    fn cross_moment(&self, s: u64, t: u64, memo: &mut HashMap<(u64, u64), f64>) -> f64 {
+        if s == 0 && t == 0 {
+            return 1.0;
+        }
+        // F is symmetric, so store each pair once.
+        let key = (s.min(t), s.max(t));
+        if let Some(&result) = memo.get(&key) {
+            return result;
+        }
+
+        let (i_s, i_t) = (s.trailing_zeros(), t.trailing_zeros());
+        let total = if i_s == i_t {
+            // Both expand along the same vertex i, pairing it with j in S
+            // and k in T. a_ij and a_ik don't appear in either remainder.
+            let i = i_s as usize;
+            let mut total = 0.0;
+            for (j, sign_j) in partners(s, i) {
+                for (k, sign_k) in partners(t, i) {
+                    let s_rest = s & !(1 << i) & !(1 << j);
+                    let t_rest = t & !(1 << i) & !(1 << k);
+                    let moment = if j == k {
+                        (self.second_moment)(i, j)
+                    } else {
+                        (self.mean)(i, j) * (self.mean)(i, k)
+                    };
+                    if moment != 0.0 {
+                        total += sign_j * sign_k * moment * self.cross_moment(s_rest, t_rest, memo);
+                    }
+                }
+            }
+            total
+        } else {
+            // The smaller vertex is only in one of the sets, so its edges
+            // are independent of the other Pfaffian entirely.
+            let (s, t) = if t == 0 || (s != 0 && i_s < i_t) {
+                (s, t)
+            } else {
+                (t, s)
+            };
+            let i = s.trailing_zeros() as usize;
+            let mut total = 0.0;
+            for (j, sign_j) in partners(s, i) {
+                let mean = (self.mean)(i, j);
+                if mean != 0.0 {
+                    let s_rest = s & !(1 << i) & !(1 << j);
+                    total += sign_j * mean * self.cross_moment(s_rest, t, memo);
+                }
+            }
+            total
+        };
+        memo.insert(key, total);
+        total
+    }
+}
+
+/// The vertices j > i left in `set` (i being its smallest), each with the
+/// sign of the pairing (i, j) in the expansion along i: + for the 1st,
+/// 3rd, ... remaining vertex, - for the 2nd, 4th, ...
+fn partners(set: u64, i: usize) -> impl Iterator<Item = (usize, f64)> {
+    let rest = set & !(1 << i);
+    (0..64)
+        .filter(move |&j| rest & (1 << j) != 0)
+        .enumerate()
+        .map(|(rank, j)| (j, if rank % 2 == 0 { 1.0 } else { -1.0 }))
+}
+
+#[cfg(all(test, feature = "rand"))]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{RngExt, SeedableRng};
+
+    /// A standard normal draw, by Box-Muller.
+    fn gaussian(rng: &mut StdRng) -> f64 {
+        let u: f64 = 1.0 - rng.random::<f64>();
+        let v: f64 = rng.random();
+        (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+
+    /// Sample means of Pf and Pf² over `draws` matrices from the ensemble,
+    /// each with its standard error.
+    fn monte_carlo(means: &[f64], variances: &[f64], draws: usize) -> [(f64, f64); 2] {
+        let n = upper_triangle_size(means.len());
+        let mut rng = StdRng::seed_from_u64(171);
+        let mut sums = [[0.0; 2]; 2];
+        for _ in 0..draws {
+            let values: Vec<f64> = means
+                .iter()
+                .zip(variances)
+                .map(|(m, v)| m + v.sqrt() * gaussian(&mut rng))
+                .collect();
+            let pf = SkewMatrix::from_upper_triangle(n, &values)
+                .unwrap()
+                .pfaffian_with_pivoting(PivotStrategy::Partial);
+            for (sum, x) in sums.iter_mut().zip([pf, pf * pf]) {
+                sum[0] += x;
+                sum[1] += x * x;
+            }
+        }
+        sums.map(|[s, s2]| {
+            let mean = s / draws as f64;
+            let variance = s2 / draws as f64 - mean * mean;
+            (mean, (variance / draws as f64).sqrt())
+        })
+    }
+
+    #[test]
+    fn expectations_match_monte_carlo_estimates() {
+        let means = [1.0, -0.5, 0.25, 0.75, 1.5, -1.0];
+        let variances = [0.5, 0.25, 1.0, 0.1, 0.3, 0.6];
+        let [(pf, pf_error), (pf2, pf2_error)] = monte_carlo(&means, &variances, 100_000);
+
+        let expected = expected_pfaffian(&means, &variances);
+        assert!(
+            (expected - pf).abs() < 4.0 * pf_error,
+            "{} vs {} ± {}",
+            expected,
+            pf,
+            pf_error
+        );
+        let expected = expected_pfaffian_squared(&means, &variances);
+        assert!(
+            (expected - pf2).abs() < 4.0 * pf2_error,
+            "{} vs {} ± {}",
+            expected,
+            pf2,
+            pf2_error
+        );
+        // The variances make E[Pf²] exceed E[Pf]² by far more than the noise.
+        assert!(expected - pf * pf > 10.0 * pf2_error);
+    }
+
+    #[test]
+    fn zero_mean_second_moment_is_the_hafnian_of_the_variances() {
+        let variances = [0.5, 0.25, 1.0, 0.1, 0.3, 0.6];
+        assert_eq!(expected_pfaffian(&[0.0; 6], &variances), 0.0);
+        // v01 v23 + v02 v13 + v03 v12
+        let hafnian = 0.5 * 0.6 + 0.25 * 0.3 + 1.0 * 0.1;
+        assert!((expected_pfaffian_squared(&[0.0; 6], &variances) - hafnian).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Everything that can go wrong when building or using a SkewMatrix.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PfaffianError {
+    /// The input matrix wasn't square.
+    NotSquare { rows: usize, cols: usize },
+    /// Two matrices that had to be the same shape weren't.
+    ShapeMismatch {
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    /// The Pfaffian is only defined for even dimensions.
+    OddDimension { n: usize },
+    /// The wrong number of upper-triangle values for an n x n matrix.
+    WrongValueCount {
+        n: usize,
+        expected: usize,
+        got: usize,
+    },
+    /// The input value at `index` (in the order it was given) is NaN or infinite.
+    NonFinite { index: usize },
+    /// A[row][col] and -A[col][row] differ by more than the tolerance
+    /// (or a diagonal entry is nonzero, when row == col).
+    NotSkewSymmetric { row: usize, col: usize },
+    /// A[row][col] and -conj(A[col][row]) differ by more than the tolerance.
+    NotAntiHermitian { row: usize, col: usize },
+    /// An edge (i, j) refers to a vertex outside 0..n.
+    EdgeOutOfRange { i: usize, j: usize, n: usize },
+    /// The edge (i, j) was given more than once (in either orientation).
+    DuplicateEdge { i: usize, j: usize },
+    /// Reading `path` failed; `message` is the underlying I/O error.
+    Io { path: PathBuf, message: String },
+    /// A matrix file couldn't be parsed at the given 1-based line.
+    Parse { line: usize, message: String },
+    /// An internal consistency check failed: Pf(A)² didn't match det(A).
+    /// `reproducer` is A in Matrix Market form, for a bug report.
+    Inconsistent {
+        pfaffian: f64,
+        determinant: f64,
+        reproducer: String,
+    },
+}
+
+impl fmt::Display for PfaffianError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PfaffianError::NotSquare { rows, cols } => {
+                write!(f, "matrix is {}x{}, expected a square matrix", rows, cols)
+            }
+            PfaffianError::ShapeMismatch { left, right } => write!(
+                f,
+                "matrices have different shapes, {}x{} and {}x{}",
+                left.0, left.1, right.0, right.1
+            ),
+            PfaffianError::OddDimension { n } => {
+                write!(f, "matrix must have even dimensions, got {}x{}", n, n)
+            }
+            PfaffianError::WrongValueCount { n, expected, got } => write!(
+                f,
+                "expected {} values for a {}x{} matrix, got {}",
+                expected, n, n, got
+            ),
+            PfaffianError::NonFinite { index } => {
+                write!(f, "value at index {} is not finite", index)
+            }
+            PfaffianError::NotSkewSymmetric { row, col } => {
+                write!(f, "matrix is not skew-symmetric at ({}, {})", row, col)
+            }
+            PfaffianError::NotAntiHermitian { row, col } => {
+                write!(f, "matrix is not anti-Hermitian at ({}, {})", row, col)
+            }
+            PfaffianError::EdgeOutOfRange { i, j, n } => {
+                write!(
+                    f,
+                    "edge ({}, {}) is out of range for a {}x{} matrix",
+                    i, j, n, n
+                )
+            }
+            PfaffianError::DuplicateEdge { i, j } => {
+                write!(f, "edge ({}, {}) was given more than once", i, j)
+            }
+            PfaffianError::Io { path, message } => {
+                write!(f, "could not read {}: {}", path.display(), message)
+            }
+            PfaffianError::Parse { line, message } => {
+                write!(f, "parse error on line {}: {}", line, message)
+            }
+            PfaffianError::Inconsistent {
+                pfaffian,
+                determinant,
+                reproducer,
+            } => write!(
+                f,
+                "Pfaffian {:e} squared doesn't match the determinant {:e}; please \
+                 report this with the matrix below\n{}",
+                pfaffian, determinant, reproducer
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PfaffianError {}
@@ -0,0 +1,204 @@
+use nalgebra::DMatrix;
+use std::collections::HashMap;
+
+#[cfg(feature = "arena")]
+use bumpalo::Bump;
+
+use crate::Ring;
+
+/// One way of folding the expansion along the first row,
+///
+///    Pf(A_S) = ∑_{j ∈ S, j ≠ i} (-1)^(position of j) A_ij Pf(A_{S \ {i, j}}),
+///
+/// with i the first index of S, into a value: the Pfaffian itself over some
+/// ring, a compensated sum, an interval, the unsigned Hafnian, ...
+///
+/// `expand_first_row` does the traversal and the memoization, and calls
+/// `start`, then `add_term` once per partner j with the already folded
+/// value for S \ {i, j}, then `finish`. The hooks let instrumentation count
+/// what the traversal does without its own copy of it.
+pub(crate) trait Expansion {
+    type Value: Clone;
+    type Sum;
+
+    /// The value for the empty index set, where every matching ends.
+    fn empty(&mut self) -> Self::Value;
+
+    fn start(&mut self) -> Self::Sum;
+
+    /// Folds in the term pairing i with j. `positive` is the sign of the
+    /// term in the Pfaffian, true for the first partner, then alternating.
+    fn add_term(
+        &mut self,
+        sum: &mut Self::Sum,
+        i: usize,
+        j: usize,
+        positive: bool,
+        sub: Self::Value,
+    );
+
+    fn finish(&mut self, sum: Self::Sum) -> Self::Value;
+
+    /// Called on entry to every subproblem, including the top-level one.
+    fn on_call(&mut self) {}
+
+    /// Called when a subproblem is answered from the memo table.
+    fn on_memo_hit(&mut self) {}
+}
+
+/// Where `expand_first_row` keeps the values of the subproblems it has done.
+pub(crate) trait Memo<V> {
+    fn get(&self, indices: &[usize]) -> Option<V>;
+    fn insert(&mut self, indices: &[usize], value: V);
+}
+
+impl<V: Clone> Memo<V> for HashMap<Vec<usize>, V> {
+    fn get(&self, indices: &[usize]) -> Option<V> {
+        HashMap::get(self, indices).cloned()
+    }
+
+    fn insert(&mut self, indices: &[usize], value: V) {
+        HashMap::insert(self, indices.to_vec(), value);
+    }
+}
+
+/// The memo table that remembers nothing, for the plain (n-1)!! recursion.
+pub(crate) struct NoMemo;
+
+impl<V> Memo<V> for NoMemo {
+    fn get(&self, _: &[usize]) -> Option<V> {
+        None
+    }
+
+    fn insert(&mut self, _: &[usize], _: V) {}
+}
+
+/// A memo table whose keys are copied into a bump arena instead of each
+/// getting its own heap allocation.
+#[cfg(feature = "arena")]
+pub(crate) struct ArenaMemo<'b, V> {
+    pub(crate) bump: &'b Bump,
+    pub(crate) table: HashMap<&'b [usize], V>,
+}
+
+#[cfg(feature = "arena")]
+impl<V: Clone> Memo<V> for ArenaMemo<'_, V> {
+    fn get(&self, indices: &[usize]) -> Option<V> {
+        self.table.get(indices).cloned()
+    }
+
+    fn insert(&mut self, indices: &[usize], value: V) {
+        self.table
+            .insert(self.bump.alloc_slice_copy(indices), value);
+    }
+}
+
+/// Folds the first-row expansion of the submatrix on `indices` (in the
+/// order given), memoizing subproblems in `memo`.
+///
+/// The index lists of the subproblems all live on one scratch stack, each
+/// pushed on top of its parent's and popped when done, so the traversal
+/// itself allocates nothing per subproblem; only the memo table does.
+pub(crate) fn expand_first_row<E: Expansion, M: Memo<E::Value>>(
+    expansion: &mut E,
+    indices: &[usize],
+    memo: &mut M,
+) -> E::Value {
+    let mut stack = indices.to_vec();
+    expand_from(expansion, memo, &mut stack, 0)
+}
+
+/// The recursion behind `expand_first_row`, on the index list
+/// `stack[start..]`.
+fn expand_from<E: Expansion, M: Memo<E::Value>>(
+    expansion: &mut E,
+    memo: &mut M,
+    stack: &mut Vec<usize>,
+    start: usize,
+) -> E::Value {
+    expansion.on_call();
+    let len = stack.len() - start;
+    if len == 0 {
+        return expansion.empty();
+    }
+    if let Some(result) = memo.get(&stack[start..]) {
+        expansion.on_memo_hit();
+        return result;
+    }
+
+    let i = stack[start];
+    let mut sum = expansion.start();
+    for j_idx in 1..len {
+        let j = stack[start + j_idx];
+        // Everything after i except j.
+        let sub_start = stack.len();
+        for k in (1..len).filter(|&k| k != j_idx) {
+            stack.push(stack[start + k]);
+        }
+        let sub = expand_from(expansion, memo, stack, sub_start);
+        stack.truncate(sub_start);
+        expansion.add_term(&mut sum, i, j, j_idx % 2 == 1, sub);
+    }
+
+    let result = expansion.finish(sum);
+    memo.insert(&stack[start..], result.clone());
+    result
+}
+
+/// The first step of the expansion, spelled out: for the first index i of
+/// `indices`, every partner j as (j, sign, the indices left once i and j
+/// are removed), in order. For walks over the matchings themselves, which
+/// need the pairs rather than a folded value.
+pub(crate) fn first_row_pairings(
+    indices: &[usize],
+) -> impl DoubleEndedIterator<Item = (usize, f64, Vec<usize>)> + '_ {
+    (1..indices.len()).map(move |j_idx| {
+        let mut rest = indices[1..].to_vec();
+        rest.remove(j_idx - 1);
+        let sign = if j_idx % 2 == 1 { 1.0 } else { -1.0 };
+        (indices[j_idx], sign, rest)
+    })
+}
+
+/// The expansion behind `SkewMatrix::pfaffian()`: the signed sum of
+/// A_ij · Pf(minor), over any ring.
+pub(crate) struct PfaffianExpansion<'a, T> {
+    pub(crate) data: &'a DMatrix<T>,
+}
+
+impl<T: Ring> Expansion for PfaffianExpansion<'_, T> {
+    type Value = T;
+    type Sum = T;
+
+    fn empty(&mut self) -> T {
+        T::one()
+    }
+
+    fn start(&mut self) -> T {
+        T::zero()
+    }
+
+    fn add_term(&mut self, sum: &mut T, i: usize, j: usize, positive: bool, sub: T) {
+        let term = self.data[(i, j)].clone() * sub;
+        let total = std::mem::replace(sum, T::zero());
+        *sum = if positive { total + term } else { total - term };
+    }
+
+    fn finish(&mut self, sum: T) -> T {
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SkewMatrix;
+
+    #[test]
+    fn memoized_and_plain_expansions_match_the_definition() {
+        let values: Vec<f64> = (1..=15).map(|k| (k as f64).sqrt() - 2.0).collect();
+        let matrix = SkewMatrix::from_upper_triangle(6, &values).unwrap();
+        let reference = matrix.pfaffian_bruteforce();
+        assert!((matrix.pfaffian() - reference).abs() < 1e-12);
+        assert!((matrix.pfaffian_naive() - reference).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,92 @@
+use nalgebra::{DMatrix, SMatrix};
+
+use crate::SkewMatrix;
+
+/// A skew-symmetric matrix whose dimension is fixed at compile time.
+/// The entries live on the stack in an nalgebra SMatrix.
+///
+/// Any N can be constructed, but `pfaffian()` is only available when N is
+/// even, so asking for the Pfaffian of an odd-sized matrix is a compile
+/// error rather than a runtime panic.
+pub struct SkewMatrixN<const N: usize> {
+    data: SMatrix<f64, N, N>,
+}
+
+/// A type-level stand-in for the dimension N, so we can put trait bounds on it.
+pub struct Dim<const N: usize>;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Implemented for `Dim<N>` only when N is even.
+/// The trait is sealed, so downstream crates can't add odd dimensions.
+pub trait EvenDim: sealed::Sealed {}
+
+macro_rules! even_dims {
+    ($($n:literal),*) => {
+        $(
+            impl sealed::Sealed for Dim<$n> {}
+            impl EvenDim for Dim<$n> {}
+        )*
+    };
+}
+
+even_dims!(0, 2, 4, 6, 8, 10, 12, 14, 16);
+
+pub type SkewMatrix2 = SkewMatrixN<2>;
+pub type SkewMatrix4 = SkewMatrixN<4>;
+pub type SkewMatrix6 = SkewMatrixN<6>;
+pub type SkewMatrix8 = SkewMatrixN<8>;
+
+impl<const N: usize> SkewMatrixN<N> {
+    /// Creates a new SkewMatrixN from its upper-triangular values, in the
+    /// same row-by-row order as `SkewMatrix::from_upper_triangle`.
+    ///
+    /// The array must have exactly N(N-1)/2 entries, and a wrong length is a
+    /// compile error. Stable Rust can't write `[f64; N * (N - 1) / 2]` as a
+    /// parameter type, so the length is a second const parameter M and a
+    /// const assertion checks it when the function is instantiated. Like
+    /// any such error it shows up in `cargo build`, not `cargo check`.
+    pub fn from_upper_triangle<const M: usize>(values: [f64; M]) -> Self {
+        const {
+            assert!(
+                M == N * N.saturating_sub(1) / 2,
+                "Incorrect number of upper-triangle values for this dimension."
+            )
+        };
+
+        let mut m = SMatrix::<f64, N, N>::zeros();
+        let positions = (0..N).flat_map(|i| ((i + 1)..N).map(move |j| (i, j)));
+
+        for ((i, j), val) in positions.zip(values) {
+            m[(i, j)] = val;
+            m[(j, i)] = -val;
+        }
+        Self { data: m }
+    }
+
+    /// Borrows the underlying stack matrix, e.g. for further nalgebra operations.
+    pub fn as_matrix(&self) -> &SMatrix<f64, N, N> {
+        &self.data
+    }
+
+    /// Consumes the SkewMatrixN and hands back the underlying stack matrix.
+    pub fn into_matrix(self) -> SMatrix<f64, N, N> {
+        self.data
+    }
+}
+
+impl<const N: usize> SkewMatrixN<N>
+where
+    Dim<N>: EvenDim,
+{
+    /// Computes the Pfaffian. Only exists for even N.
+    pub fn pfaffian(&self) -> f64 {
+        // Hand the entries over to the dynamic matrix, which already knows
+        // how to do the matching recursion.
+        let dynamic =
+            SkewMatrix::from_skew_unchecked(DMatrix::from_column_slice(N, N, self.data.as_slice()));
+        dynamic.pfaffian()
+    }
+}
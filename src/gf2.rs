@@ -0,0 +1,138 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_traits::{One, Zero};
+
+/// An element of GF(2)[x] / (f(x)), a polynomial over GF(2) reduced modulo
+/// a fixed polynomial f.
+///
+/// Polynomials are bitmasks, bit k being the coefficient of x^k, and F is
+/// f's bitmask: `Gf2Mod<0b111>` is GF(2)[x] / (x² + x + 1), which is GF(4).
+/// For an irreducible f of degree d this is the field GF(2^d), but any f of
+/// degree 1 to 63 gives a ring. Addition is XOR, so subtraction and
+/// negation are the same as addition, and multiplication is a carry-less
+/// product followed by reduction mod f.
+///
+/// This is a `Ring`, so `SkewMatrix<Gf2Mod<F>>::pfaffian()` runs the
+/// division-free recursion with every operation done mod f. In
+/// characteristic 2 a skew matrix is just a symmetric one with zero
+/// diagonal, and the signs in the expansion all disappear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gf2Mod<const F: u64> {
+    bits: u64,
+}
+
+impl<const F: u64> Gf2Mod<F> {
+    /// The degree d of f. Using the type with a constant f (F = 0 or 1) is
+    /// a compile error.
+    pub const DEGREE: u32 = {
+        assert!(F > 1, "The modulus must have degree at least 1.");
+        63 - F.leading_zeros()
+    };
+
+    /// The polynomial with coefficient bits `bits`, reduced mod f.
+    pub fn new(bits: u64) -> Self {
+        Self::reduce(bits as u128)
+    }
+
+    /// The coefficient bits of the reduced polynomial, of degree below d.
+    pub fn bits(&self) -> u64 {
+        self.bits
+    }
+
+    fn reduce(mut value: u128) -> Self {
+        let d = Self::DEGREE;
+        while value >> d != 0 {
+            let top = 127 - value.leading_zeros();
+            value ^= (F as u128) << (top - d);
+        }
+        Self { bits: value as u64 }
+    }
+}
+
+impl<const F: u64> Add for Gf2Mod<F> {
+    type Output = Self;
+
+    // Coefficients are mod 2, so adding them is XOR.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            bits: self.bits ^ rhs.bits,
+        }
+    }
+}
+
+impl<const F: u64> Sub for Gf2Mod<F> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        self + rhs
+    }
+}
+
+impl<const F: u64> Neg for Gf2Mod<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+impl<const F: u64> Mul for Gf2Mod<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // Schoolbook carry-less multiplication, then one reduction.
+        let mut product = 0u128;
+        let mut b = rhs.bits;
+        let mut shift = 0;
+        while b != 0 {
+            if b & 1 == 1 {
+                product ^= (self.bits as u128) << shift;
+            }
+            b >>= 1;
+            shift += 1;
+        }
+        Self::reduce(product)
+    }
+}
+
+impl<const F: u64> Zero for Gf2Mod<F> {
+    fn zero() -> Self {
+        Self { bits: 0 }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.bits == 0
+    }
+}
+
+impl<const F: u64> One for Gf2Mod<F> {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkewMatrix;
+
+    type Gf4 = Gf2Mod<0b111>;
+
+    #[test]
+    fn pfaffian_over_gf4_matches_the_hand_computation() {
+        let (one, x, x1) = (Gf4::new(1), Gf4::new(0b10), Gf4::new(0b11));
+        // x² = x + 1, so x (x + 1) = 1 and (x + 1)² = x.
+        assert_eq!(x * x, x1);
+        assert_eq!(x * x1, one);
+        assert_eq!(x1 * x1, x);
+        assert_eq!(Gf4::new(0b100), x1);
+
+        // Pf = a01 a23 + a02 a13 + a03 a12, with no signs in characteristic 2:
+        // x (x + 1) + 1 (x + 1) + (x + 1) x = 1 + (x + 1) + 1 = x + 1.
+        let matrix = SkewMatrix::from_upper_triangle_generic(4, &[x, one, x1, x, x1, x1]).unwrap();
+        assert_eq!(matrix.pfaffian(), x1);
+        assert_eq!(matrix.pfaffian().bits(), 0b11);
+    }
+}
@@ -0,0 +1,117 @@
+use nalgebra::DMatrix;
+use std::collections::HashMap;
+
+use crate::Semiring;
+use crate::expansion::{Expansion, expand_first_row};
+
+/// Computes the Hafnian of a symmetric matrix: the sum over perfect matchings
+/// of the product of matched entries, with no signs.
+///
+/// This is the same recursion as `SkewMatrix::pfaffian`, just without the
+/// alternating sign. Unlike the Pfaffian there's no elimination shortcut
+/// (computing the Hafnian is #P-hard), so the cost is exponential in n even
+/// with memoization. Only use it for small matrices.
+///
+/// The diagonal is ignored, and odd-sized matrices have no perfect matchings,
+/// so their Hafnian is 0. Panics if the matrix isn't square.
+pub fn hafnian(symmetric: &DMatrix<f64>) -> f64 {
+    hafnian_generic(symmetric)
+}
+
+/// The Hafnian over any commutative `Semiring`: the sum over perfect
+/// matchings {(i_1, j_1), ...} (each i_k < j_k) of ∏ m[i_k][j_k], with no
+/// signs, so only the upper triangle is read.
+///
+/// This is the unsigned counterpart of `SkewMatrix::pfaffian()`, and over a
+/// semiring without subtraction it is the only matching sum there is. With
+/// `MaxPlus` entries it gives the weight of a maximum-weight perfect
+/// matching. Odd-sized matrices have no perfect matchings, so the result is
+/// the semiring's zero. Panics if the matrix isn't square.
+pub fn hafnian_generic<T: Semiring>(m: &DMatrix<T>) -> T {
+    assert!(m.is_square(), "Matrix must be square.");
+    let n = m.nrows();
+    if n % 2 == 1 {
+        return T::zero();
+    }
+    let initial_indices: Vec<usize> = (0..n).collect();
+    subset_hafnian(m, &initial_indices, &mut HashMap::new())
+}
+
+/// The Hafnian of the submatrix of m on `indices`, with the subproblems
+/// memoized in `memo`, which can be shared between calls on the same m.
+pub(crate) fn subset_hafnian<T: Semiring>(
+    m: &DMatrix<T>,
+    indices: &[usize],
+    memo: &mut HashMap<Vec<usize>, T>,
+) -> T {
+    expand_first_row(&mut HafnianExpansion { data: m }, indices, memo)
+}
+
+/// `pfaffian()`'s expansion without the signs, over a semiring.
+struct HafnianExpansion<'a, T> {
+    data: &'a DMatrix<T>,
+}
+
+impl<T: Semiring> Expansion for HafnianExpansion<'_, T> {
+    type Value = T;
+    type Sum = T;
+
+    fn empty(&mut self) -> T {
+        T::one()
+    }
+
+    fn start(&mut self) -> T {
+        T::zero()
+    }
+
+    fn add_term(&mut self, sum: &mut T, i: usize, j: usize, _positive: bool, sub: T) {
+        let total = std::mem::replace(sum, T::zero());
+        *sum = total + self.data[(i, j)].clone() * sub;
+    }
+
+    fn finish(&mut self, sum: T) -> T {
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MaxPlus;
+
+    #[test]
+    fn hafnian_of_the_all_ones_matrix_counts_matchings() {
+        let ones = DMatrix::from_element(6, 6, 1.0);
+        assert_eq!(hafnian(&ones), 15.0);
+        assert_eq!(hafnian(&DMatrix::from_element(5, 5, 1.0)), 0.0);
+    }
+
+    #[test]
+    fn hafnian_of_a_weighted_4x4_sums_the_three_matchings() {
+        // Haf = a01 a23 + a02 a13 + a03 a12 = 1·9 + 2·5 + 3·4.
+        let m = DMatrix::from_row_slice(
+            4,
+            4,
+            &[
+                0.0, 1.0, 2.0, 3.0, //
+                1.0, 0.0, 4.0, 5.0, //
+                2.0, 4.0, 0.0, 9.0, //
+                3.0, 5.0, 9.0, 0.0,
+            ],
+        );
+        assert_eq!(hafnian(&m), 31.0);
+    }
+
+    #[test]
+    fn max_plus_hafnian_is_the_heaviest_matching() {
+        // Matchings of 4: {01, 23} = 1 + 9, {02, 13} = 2 + 5, {03, 12} = 3 + 4.
+        let w = [
+            [0.0, 1.0, 2.0, 3.0],
+            [1.0, 0.0, 4.0, 5.0],
+            [2.0, 4.0, 0.0, 9.0],
+            [3.0, 5.0, 9.0, 0.0],
+        ];
+        let m = DMatrix::from_fn(4, 4, |i, j| MaxPlus(w[i][j]));
+        assert_eq!(hafnian_generic(&m), MaxPlus(10.0));
+    }
+}
@@ -0,0 +1,469 @@
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::{PivotStrategy, SkewMatrix};
+
+/// A work budget for `SkewMatrix::pfaffian_best_effort`, counted in inner
+/// update steps of the exact elimination (about n^3 / 12 for an n x n
+/// matrix).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    pub max_steps: u64,
+}
+
+impl Budget {
+    pub fn new(max_steps: u64) -> Self {
+        Self { max_steps }
+    }
+}
+
+/// Computes the Pfaffian of an integer skew matrix exactly in i128, or
+/// returns None if an intermediate value overflows.
+///
+/// This is the skew analogue of Bareiss' fraction-free elimination. At step
+/// k, with pivot p = A[k][k+1] and the previous pivot q, every trailing
+/// entry becomes
+///
+///    A[i][j] ← (p·A[i][j] - A[k][i]·A[k+1][j] + A[k][j]·A[k+1][i]) / q
+///
+/// and by Tanner's Pfaffian identity the division is always exact: the new
+/// entry is the Pfaffian of the leading 2k + 2 rows/cols together with i and
+/// j. So everything stays an integer, no entry is ever larger than a
+/// Pfaffian of a submatrix (times one pivot, mid-update), and the final
+/// pivot is Pf(A). This needs O(n^3) i128 operations, against the
+/// exponential memoized recursion a bignum `SkewMatrix<BigInt>` would run.
+///
+/// Rows can be anything that derefs to a slice, e.g. `[i64; N]` or
+/// `Vec<i64>`. Panics if the matrix isn't square, even-sized and
+/// skew-symmetric.
+pub fn pfaffian_i128<R: AsRef<[i64]>>(matrix: &[R]) -> Option<i128> {
+    fraction_free_pfaffian(checked_rows(matrix))
+}
+
+/// Widens the rows to i128, panicking unless they form a square, even-sized,
+/// skew-symmetric matrix.
+fn checked_rows<R: AsRef<[i64]>>(matrix: &[R]) -> Vec<Vec<i128>> {
+    let n = matrix.len();
+    assert!(n.is_multiple_of(2), "The Pfaffian needs an even dimension.");
+    let a: Vec<Vec<i128>> = matrix
+        .iter()
+        .map(|row| {
+            let row = row.as_ref();
+            assert_eq!(row.len(), n, "Matrix must be square.");
+            row.iter().map(|&x| x as i128).collect()
+        })
+        .collect();
+    let mut pairs = (0..n).flat_map(|i| (i..n).map(move |j| (i, j)));
+    if let Some((i, j)) = pairs.find(|&(i, j)| a[i][j] != -a[j][i]) {
+        panic!("Matrix is not skew-symmetric at ({}, {}).", i, j);
+    }
+    a
+}
+
+/// Computes Pf(A) mod p for an integer skew matrix and a prime p.
+///
+/// Over the field Z/p this is plain skew elimination with the update of
+/// `SparsityPlan::pfaffian`, dividing by each pivot through its inverse
+/// p^(p-2), in O(n^3) operations on numbers below p. Entries are first
+/// reduced into 0..p, so negative entries are fine. p isn't checked for
+/// primality, and a composite p gives a meaningless answer.
+/// Panics like `pfaffian_i128`, or if p < 2.
+pub fn pfaffian_mod_p<R: AsRef<[i64]>>(matrix: &[R], p: u64) -> u64 {
+    assert!(p >= 2, "The modulus must be a prime.");
+    let modulus = p as i128;
+    let a = checked_rows(matrix)
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|x| x.rem_euclid(modulus) as u64)
+                .collect()
+        })
+        .collect();
+    eliminate_mod_p(a, p)
+}
+
+// With p close to 2^64 even a sum of two residues can overflow a u64, so
+// everything goes through u128.
+fn add_mod(a: u64, b: u64, p: u64) -> u64 {
+    ((a as u128 + b as u128) % p as u128) as u64
+}
+
+fn sub_mod(a: u64, b: u64, p: u64) -> u64 {
+    add_mod(a, p - b, p)
+}
+
+fn mul_mod(a: u64, b: u64, p: u64) -> u64 {
+    (a as u128 * b as u128 % p as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exponent: u64, p: u64) -> u64 {
+    let mut result = 1 % p;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, p);
+        }
+        base = mul_mod(base, base, p);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// The elimination behind `pfaffian_mod_p`, on entries already in 0..p.
+pub(crate) fn eliminate_mod_p(mut a: Vec<Vec<u64>>, p: u64) -> u64 {
+    let n = a.len();
+    let mut pf = 1 % p;
+    for k in (0..n).step_by(2) {
+        let Some(kp) = ((k + 1)..n).find(|&j| a[k][j] != 0) else {
+            return 0;
+        };
+        if kp != k + 1 {
+            a.swap(k + 1, kp);
+            for row in a.iter_mut() {
+                row.swap(k + 1, kp);
+            }
+            pf = sub_mod(0, pf, p);
+        }
+
+        let pivot = a[k][k + 1];
+        pf = mul_mod(pf, pivot, p);
+        let inverse = pow_mod(pivot, p - 2, p);
+        // A[i][j] += (A[k+1][i] A[k][j] - A[k][i] A[k+1][j]) / A[k][k+1]
+        for i in (k + 2)..n {
+            for j in (i + 1)..n {
+                let plus = mul_mod(a[k + 1][i], a[k][j], p);
+                let minus = mul_mod(a[k][i], a[k + 1][j], p);
+                let delta = mul_mod(sub_mod(plus, minus, p), inverse, p);
+                a[i][j] = add_mod(a[i][j], delta, p);
+                a[j][i] = sub_mod(0, a[i][j], p);
+            }
+        }
+    }
+    pf
+}
+
+/// The first prime `pfaffian_crt` works modulo, the largest below 2^64.
+pub(crate) const FIRST_CRT_PRIME: u64 = u64::MAX - 58;
+
+/// Computes the exact Pf(A) of an integer skew matrix from its residues
+/// modulo large primes, given a bound |Pf(A)| ≤ `bound`.
+///
+/// Pf(A) mod p for primes p_1, p_2, ... pins down Pf(A) modulo their
+/// product M by the Chinese remainder theorem, and that's Pf(A) itself once
+/// M > 2 · bound. Primes are taken downwards from 2^64 until their product
+/// gets there, each residue is an independent elimination on 64-bit
+/// numbers, and the residues are combined one at a time (Garner's
+/// algorithm) into a `BigInt`, so neither the bound nor the result has a
+/// size limit and no intermediate can overflow. Each prime adds about 64
+/// bits. A bound can come from Hadamard's inequality,
+/// |Pf(A)|² = |det A| ≤ ∏ ‖row_i‖. If the bound is wrong, so is the answer.
+/// Panics like `pfaffian_i128`.
+pub fn pfaffian_crt<R: AsRef<[i64]>>(matrix: &[R], bound: &BigInt) -> BigInt {
+    let rows = checked_rows(matrix);
+    let target = (bound * 2u32).to_biguint().unwrap_or_default();
+    let mut value = BigUint::zero();
+    let mut modulus = BigUint::one();
+    for p in crt_primes() {
+        let modulus_i = p as i128;
+        let reduced = rows
+            .iter()
+            .map(|row| row.iter().map(|x| x.rem_euclid(modulus_i) as u64).collect())
+            .collect();
+        let residue = eliminate_mod_p(reduced, p);
+
+        // value ≡ Pf(A) mod `modulus`. Adding modulus · t with
+        // t = (residue - value) / modulus mod p keeps that and makes it
+        // right mod p as well.
+        let value_mod_p = big_mod(&value, p);
+        let modulus_mod_p = big_mod(&modulus, p);
+        let t = mul_mod(
+            sub_mod(residue, value_mod_p, p),
+            pow_mod(modulus_mod_p, p - 2, p),
+            p,
+        );
+        value += &modulus * t;
+        modulus *= p;
+        if modulus > target {
+            break;
+        }
+    }
+    symmetric_residue(value, modulus)
+}
+
+/// The primes below 2^64, largest first.
+fn crt_primes() -> impl Iterator<Item = u64> {
+    (0..=FIRST_CRT_PRIME)
+        .rev()
+        .step_by(2)
+        .filter(|&p| is_prime(p))
+}
+
+/// Deterministic Miller–Rabin: the first twelve primes as witnesses are
+/// enough for every n < 2^64.
+fn is_prime(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&w) = WITNESSES.iter().find(|&&w| n.is_multiple_of(w)) {
+        return n == w;
+    }
+    let shift = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> shift;
+    WITNESSES.iter().all(|&w| {
+        let mut x = pow_mod(w, odd, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..shift {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+fn big_mod(x: &BigUint, p: u64) -> u64 {
+    (x % p).to_u64().expect("A residue fits in a u64.")
+}
+
+/// The representative of x mod m in (-m/2, m/2].
+fn symmetric_residue(x: BigUint, modulus: BigUint) -> BigInt {
+    if &x * 2u32 > modulus {
+        BigInt::from(x) - BigInt::from(modulus)
+    } else {
+        BigInt::from(x)
+    }
+}
+
+/// The fraction-free elimination behind `pfaffian_i128`, on a matrix the
+/// caller has already checked.
+fn fraction_free_pfaffian(mut a: Vec<Vec<i128>>) -> Option<i128> {
+    let n = a.len();
+    let mut sign: i128 = 1;
+    let mut prev: i128 = 1;
+    for k in (0..n).step_by(2) {
+        // Any nonzero entry in row k will do as the pivot, since all the
+        // entries are exact. A zero row means Pf(A) = 0.
+        let Some(kp) = ((k + 1)..n).find(|&j| a[k][j] != 0) else {
+            return Some(0);
+        };
+        if kp != k + 1 {
+            a.swap(k + 1, kp);
+            for row in a.iter_mut() {
+                row.swap(k + 1, kp);
+            }
+            sign = -sign;
+        }
+
+        let p = a[k][k + 1];
+        for i in (k + 2)..n {
+            for j in (i + 1)..n {
+                let numerator = p
+                    .checked_mul(a[i][j])?
+                    .checked_sub(a[k][i].checked_mul(a[k + 1][j])?)?
+                    .checked_add(a[k][j].checked_mul(a[k + 1][i])?)?;
+                debug_assert_eq!(numerator % prev, 0);
+                let value = numerator / prev;
+                a[i][j] = value;
+                a[j][i] = value.checked_neg()?;
+            }
+        }
+        prev = p;
+    }
+    sign.checked_mul(prev)
+}
+
+/// How many inner updates `fraction_free_pfaffian` does on an n x n matrix:
+/// ∑ over the steps of (m choose 2), for trailing sizes m = n - 2, n - 4, ...
+fn fraction_free_steps(n: usize) -> u64 {
+    (0..n)
+        .step_by(2)
+        .map(|k| {
+            let m = (n - k - 2) as u64;
+            m * m.saturating_sub(1) / 2
+        })
+        .sum()
+}
+
+/// The smallest s >= 0 with x · 2^s an integer, for finite x.
+fn binary_denominator_exponent(x: f64) -> i32 {
+    if x == 0.0 {
+        return 0;
+    }
+    let bits = x.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let mut mantissa = bits & ((1 << 52) - 1);
+    // x = mantissa · 2^exponent, with the implicit bit for normal numbers.
+    let exponent = if biased == 0 {
+        -1074
+    } else {
+        mantissa |= 1 << 52;
+        biased - 1075
+    };
+    (-(exponent + mantissa.trailing_zeros() as i32)).max(0)
+}
+
+impl SkewMatrix {
+    /// Computes the Pfaffian exactly if that fits in `budget`, and falls
+    /// back to floating point otherwise. Returns (Pfaffian, exact).
+    ///
+    /// Every finite f64 is a dyadic rational, so for a common s the matrix
+    /// 2^s A has integer entries. If those fit in an i64 and the elimination
+    /// fits in the budget, `pfaffian_i128`'s fraction-free elimination gives
+    /// Pf(2^s A) = 2^(s·n/2) Pf(A) exactly, and the result is that value
+    /// rounded once to f64 (barring underflow), flagged `true`. Otherwise
+    /// (too much work, an i128 overflow, entries that can't be scaled, or a
+    /// NaN/infinite entry) this is `pfaffian_with_pivoting(Partial)`, flagged
+    /// `false`. The exact path never needs a bignum library.
+    pub fn pfaffian_best_effort(&self, budget: Budget) -> (f64, bool) {
+        let fallback = || (self.pfaffian_with_pivoting(PivotStrategy::Partial), false);
+        let n = self.data.nrows();
+        if fraction_free_steps(n) > budget.max_steps || self.data.iter().any(|x| !x.is_finite()) {
+            return fallback();
+        }
+
+        let scale = self
+            .data
+            .iter()
+            .map(|&x| binary_denominator_exponent(x))
+            .max()
+            .unwrap_or(0);
+        if scale > 1023 {
+            return fallback();
+        }
+        // Multiplying by a power of two is exact unless it overflows.
+        let factor = 2f64.powi(scale);
+        let mut a = vec![vec![0i128; n]; n];
+        for (i, row) in a.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                let scaled = self.data[(i, j)] * factor;
+                if scaled.abs() >= 2f64.powi(63) {
+                    return fallback();
+                }
+                *entry = scaled as i128;
+            }
+        }
+        let Some(pf) = fraction_free_pfaffian(a) else {
+            return fallback();
+        };
+
+        // Undo the scaling in steps, since 2^(-s·n/2) alone can underflow.
+        let mut result = pf as f64;
+        let mut remaining = scale as usize * n / 2;
+        while remaining > 0 {
+            let step = remaining.min(1000);
+            result *= 2f64.powi(-(step as i32));
+            remaining -= step;
+        }
+        (result, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The block-diagonal matrix with 2x2 blocks [[0, x], [-x, 0]], whose
+    /// Pfaffian is the product of the x's.
+    fn block_diagonal(entries: &[i64]) -> Vec<Vec<i64>> {
+        let n = 2 * entries.len();
+        let mut a = vec![vec![0; n]; n];
+        for (k, &x) in entries.iter().enumerate() {
+            a[2 * k][2 * k + 1] = x;
+            a[2 * k + 1][2 * k] = -x;
+        }
+        a
+    }
+
+    #[test]
+    fn crt_matches_i128_on_small_matrices() {
+        let a = vec![
+            vec![0, 3, -1, 4],
+            vec![-3, 0, 5, -9],
+            vec![1, -5, 0, 2],
+            vec![-4, 9, -2, 0],
+        ];
+        let exact = pfaffian_i128(&a).unwrap();
+        assert_eq!(exact, 3 * 2 - (-1) * (-9) + 4 * 5);
+        assert_eq!(pfaffian_crt(&a, &BigInt::from(1000)), BigInt::from(exact));
+    }
+
+    #[test]
+    fn crt_reconstructs_pfaffians_beyond_i128() {
+        let entries = [i64::MAX, i64::MIN + 1, i64::MAX - 2, i64::MAX];
+        let expected: BigInt = entries.iter().map(|&x| BigInt::from(x)).product();
+        assert!(expected.bits() > 250);
+        let bound = BigInt::one() << 256;
+        assert_eq!(pfaffian_crt(&block_diagonal(&entries), &bound), expected);
+    }
+
+    #[test]
+    fn crt_primes_start_below_2_64() {
+        let primes: Vec<u64> = crt_primes().take(3).collect();
+        assert_eq!(primes, [u64::MAX - 58, u64::MAX - 82, u64::MAX - 94]);
+        assert!(!is_prime(u64::MAX));
+        assert!(is_prime(2) && is_prime(37) && !is_prime(1));
+    }
+
+    #[test]
+    fn i128_matches_the_bigint_recursion_and_detects_overflow() {
+        for n in [2, 4, 6, 8] {
+            let a: Vec<Vec<i64>> = (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| match i.cmp(&j) {
+                            std::cmp::Ordering::Less => ((i * 11 + j * 5) % 19) as i64 - 9,
+                            std::cmp::Ordering::Greater => 9 - ((j * 11 + i * 5) % 19) as i64,
+                            std::cmp::Ordering::Equal => 0,
+                        })
+                        .collect()
+                })
+                .collect();
+            let upper: Vec<BigInt> = (0..n)
+                .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+                .map(|(i, j)| BigInt::from(a[i][j]))
+                .collect();
+            let expected = SkewMatrix::from_upper_triangle_generic(n, &upper)
+                .unwrap()
+                .pfaffian();
+            assert_eq!(
+                pfaffian_i128(&a).map(BigInt::from),
+                Some(expected),
+                "n = {}",
+                n
+            );
+        }
+
+        // (2^63 - 1)^3 needs about 189 bits.
+        assert_eq!(pfaffian_i128(&block_diagonal(&[i64::MAX; 3])), None);
+        assert_eq!(
+            pfaffian_i128(&block_diagonal(&[i64::MAX; 2])),
+            Some(i64::MAX as i128 * i64::MAX as i128)
+        );
+    }
+
+    #[test]
+    fn best_effort_is_exact_when_small_and_falls_back_when_large() {
+        // Pf = 0.5 · 6 - 0.25 · 5 + 3 · 0.125 = 2.125, all exactly dyadic.
+        let small = SkewMatrix::from_upper_triangle(4, &[0.5, 0.25, 3.0, 0.125, 5.0, 6.0]).unwrap();
+        assert_eq!(small.pfaffian_best_effort(Budget::new(1000)), (2.125, true));
+
+        let n = 40;
+        let values: Vec<f64> = (0..n * (n - 1) / 2)
+            .map(|k| ((k * 7) % 5) as f64 - 2.0)
+            .collect();
+        let large = SkewMatrix::from_upper_triangle(n, &values).unwrap();
+        let (pf, exact) = large.pfaffian_best_effort(Budget::new(1000));
+        assert!(!exact);
+        assert_eq!(pf, large.pfaffian_with_pivoting(PivotStrategy::Partial));
+        assert!(large.pfaffian_best_effort(Budget::new(u64::MAX)).1);
+
+        // Within budget, but Pf = 2^160 overflows i128.
+        let rows = block_diagonal(&[1 << 40; 4]);
+        let dense = nalgebra::DMatrix::from_fn(8, 8, |i, j| rows[i][j] as f64);
+        let blocks = SkewMatrix::from_skew_unchecked(dense);
+        assert!(!blocks.pfaffian_best_effort(Budget::new(u64::MAX)).1);
+    }
+}
@@ -0,0 +1,243 @@
+use nalgebra::DMatrix;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{PfaffianError, PivotStrategy, SkewMatrix};
+
+/// How far A[i][j] and -A[j][i] may differ in a file. Values printed with
+/// less than full precision won't round to exact negatives of each other.
+const FILE_TOLERANCE: f64 = 1e-9;
+
+/// The on-disk formats we can read a matrix from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixFormat {
+    /// One row per line, entries separated by commas. Blank lines are skipped.
+    Csv,
+    /// A Matrix Market coordinate file ("%%MatrixMarket matrix coordinate
+    /// real general" or "... skew-symmetric"). Skew-symmetric files only
+    /// list the lower triangle, as the format specifies.
+    MatrixMarket,
+}
+
+impl MatrixFormat {
+    /// The file extension this format is recognised by, without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MatrixFormat::Csv => "csv",
+            MatrixFormat::MatrixMarket => "mtx",
+        }
+    }
+
+    /// Parses the contents of a file in this format.
+    pub fn parse(&self, text: &str) -> Result<SkewMatrix, PfaffianError> {
+        match self {
+            MatrixFormat::Csv => parse_csv(text),
+            MatrixFormat::MatrixMarket => parse_matrix_market(text),
+        }
+    }
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> PfaffianError {
+    PfaffianError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+fn parse_value(line: usize, token: &str) -> Result<f64, PfaffianError> {
+    token
+        .trim()
+        .parse()
+        .map_err(|_| parse_error(line, format!("{:?} is not a number", token.trim())))
+}
+
+fn parse_csv(text: &str) -> Result<SkewMatrix, PfaffianError> {
+    let mut rows: Vec<Vec<f64>> = Vec::new();
+    for (k, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = line
+            .split(',')
+            .map(|token| parse_value(k + 1, token))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(first) = rows.first()
+            && row.len() != first.len()
+        {
+            return Err(parse_error(
+                k + 1,
+                format!("expected {} columns, got {}", first.len(), row.len()),
+            ));
+        }
+        rows.push(row);
+    }
+
+    let nrows = rows.len();
+    let ncols = rows.first().map_or(0, |r| r.len());
+    let data = DMatrix::from_fn(nrows, ncols, |i, j| rows[i][j]);
+    SkewMatrix::from_matrix(data, FILE_TOLERANCE)
+}
+
+fn parse_matrix_market(text: &str) -> Result<SkewMatrix, PfaffianError> {
+    let mut lines = text.lines().enumerate();
+
+    let header = lines
+        .next()
+        .map(|(_, l)| l.to_lowercase())
+        .unwrap_or_default();
+    let fields: Vec<&str> = header.split_whitespace().collect();
+    if fields.len() != 5 || fields[0] != "%%matrixmarket" || fields[1] != "matrix" {
+        return Err(parse_error(1, "missing %%MatrixMarket matrix header"));
+    }
+    if fields[2] != "coordinate" || fields[3] != "real" {
+        return Err(parse_error(
+            1,
+            "only real coordinate matrices are supported",
+        ));
+    }
+    let skew = match fields[4] {
+        "general" => false,
+        "skew-symmetric" => true,
+        other => return Err(parse_error(1, format!("unsupported symmetry {:?}", other))),
+    };
+
+    let mut data: Option<DMatrix<f64>> = None;
+    for (k, line) in lines {
+        let line_no = k + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        let Some(m) = data.as_mut() else {
+            // The first non-comment line is "rows cols entries".
+            let [rows, cols, _] = tokens[..] else {
+                return Err(parse_error(line_no, "expected \"rows cols entries\""));
+            };
+            let parse_dim = |t: &str| {
+                t.parse::<usize>()
+                    .map_err(|_| parse_error(line_no, format!("{:?} is not a size", t)))
+            };
+            data = Some(DMatrix::zeros(parse_dim(rows)?, parse_dim(cols)?));
+            continue;
+        };
+
+        let [i, j, v] = tokens[..] else {
+            return Err(parse_error(line_no, "expected \"row col value\""));
+        };
+        let index = |t: &str, len: usize| match t.parse::<usize>() {
+            Ok(x) if (1..=len).contains(&x) => Ok(x - 1),
+            _ => Err(parse_error(line_no, format!("index {:?} out of range", t))),
+        };
+        let (i, j) = (index(i, m.nrows())?, index(j, m.ncols())?);
+        let v = parse_value(line_no, v)?;
+        m[(i, j)] = v;
+        if skew {
+            m[(j, i)] = -v;
+        }
+    }
+
+    let data = data.ok_or_else(|| parse_error(1, "missing size line"))?;
+    SkewMatrix::from_matrix(data, FILE_TOLERANCE)
+}
+
+impl SkewMatrix {
+    /// Writes the matrix as a Matrix Market skew-symmetric file, which
+    /// `MatrixFormat::MatrixMarket` reads back exactly.
+    ///
+    /// Only the nonzero entries below the diagonal are listed, as the format
+    /// specifies, and every value is printed in full, so a matrix that made
+    /// some computation misbehave reproduces it bit for bit elsewhere.
+    pub fn to_matrix_market(&self) -> String {
+        let n = self.data.nrows();
+        let entries: Vec<(usize, usize, f64)> = (0..n)
+            .flat_map(|j| ((j + 1)..n).map(move |i| (i, j)))
+            .map(|(i, j)| (i, j, self.data[(i, j)]))
+            .filter(|&(_, _, value)| value != 0.0)
+            .collect();
+        let mut out = format!(
+            "%%MatrixMarket matrix coordinate real skew-symmetric\n{} {} {}\n",
+            n,
+            n,
+            entries.len()
+        );
+        for (i, j, value) in entries {
+            out.push_str(&format!("{} {} {:?}\n", i + 1, j + 1, value));
+        }
+        out
+    }
+}
+
+/// Reads a single matrix file in the given format.
+pub fn read_matrix(path: &Path, format: MatrixFormat) -> Result<SkewMatrix, PfaffianError> {
+    let text = fs::read_to_string(path).map_err(|e| PfaffianError::Io {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    format.parse(&text)
+}
+
+/// Loads every file in `path` with the format's extension (see
+/// `MatrixFormat::extension`) and computes its Pfaffian by partial-pivoting
+/// elimination, in parallel when the `rayon` feature is on.
+///
+/// Like `pfaffian_batch`, one bad file doesn't sink the whole run: each file
+/// gets its own Result, paired with its path, in sorted path order. Only a
+/// failure to list the directory itself is returned as the outer error.
+#[allow(clippy::type_complexity)]
+pub fn pfaffian_dir(
+    path: &Path,
+    format: MatrixFormat,
+) -> Result<Vec<(PathBuf, Result<f64, PfaffianError>)>, PfaffianError> {
+    let io_error = |e: std::io::Error| PfaffianError::Io {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    };
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path).map_err(io_error)? {
+        let file = entry.map_err(io_error)?.path();
+        if file.is_file() && file.extension().is_some_and(|e| e == format.extension()) {
+            files.push(file);
+        }
+    }
+    files.sort();
+
+    let compute = |file: PathBuf| {
+        let result =
+            read_matrix(&file, format).map(|m| m.pfaffian_with_pivoting(PivotStrategy::Partial));
+        (file, result)
+    };
+
+    #[cfg(feature = "rayon")]
+    let results = files.into_par_iter().map(compute).collect();
+    #[cfg(not(feature = "rayon"))]
+    let results = files.into_iter().map(compute).collect();
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matrix_market_round_trip_is_exact_near_f64_max() {
+        let values = [f64::MAX, -0.1, 3.0e300, 1.0 / 3.0, 0.0, -f64::MAX / 1.5];
+        let matrix = SkewMatrix::from_upper_triangle(4, &values).unwrap();
+        let text = matrix.to_matrix_market();
+        let read_back = MatrixFormat::MatrixMarket.parse(&text).unwrap();
+        assert_eq!(read_back.as_matrix(), matrix.as_matrix());
+        assert_eq!(read_back.as_matrix()[(0, 1)], f64::MAX);
+        assert_eq!(read_back.as_matrix()[(1, 0)], -f64::MAX);
+
+        let tiny = SkewMatrix::from_upper_triangle(2, &[f64::from_bits(1)]).unwrap();
+        let read_back = MatrixFormat::MatrixMarket
+            .parse(&tiny.to_matrix_market())
+            .unwrap();
+        assert_eq!(read_back.as_matrix(), tiny.as_matrix());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::elimination::eliminate_in_place;
+use crate::{PivotStrategy, SkewMatrix};
+
+/// Iterator adaptor returned by `PfaffianIteratorExt::pfaffians`.
+pub struct Pfaffians<I> {
+    inner: I,
+    pivots: Vec<f64>,
+}
+
+impl<I: Iterator<Item = SkewMatrix>> Iterator for Pfaffians<I> {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        let mut m = self.inner.next()?;
+        // We own each matrix, so the elimination can run in its storage
+        // directly. The pivot buffer is shared across the whole stream.
+        let sign = eliminate_in_place(&mut m.data, PivotStrategy::Partial, &mut self.pivots);
+        Some(sign * self.pivots.iter().product::<f64>())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Adds `.pfaffians()` to any iterator of SkewMatrix values.
+pub trait PfaffianIteratorExt: Iterator<Item = SkewMatrix> + Sized {
+    /// Lazily maps each matrix to its Pfaffian (by partial-pivoting elimination),
+    /// without allocating any per-matrix scratch space.
+    fn pfaffians(self) -> Pfaffians<Self> {
+        Pfaffians {
+            inner: self,
+            pivots: Vec::new(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = SkewMatrix>> PfaffianIteratorExt for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pfaffians_adaptor_matches_mapping_each_matrix() {
+        let matrices: Vec<SkewMatrix> = (0..5)
+            .map(|k| {
+                let values: Vec<f64> = (0..15).map(|v| ((v * 3 + k) % 7) as f64 - 3.0).collect();
+                SkewMatrix::from_upper_triangle(6, &values).unwrap()
+            })
+            .chain([
+                SkewMatrix::empty(),
+                SkewMatrix::from_upper_triangle(2, &[4.0]).unwrap(),
+            ])
+            .collect();
+
+        let expected: Vec<f64> = matrices
+            .iter()
+            .map(|m| m.pfaffian_with_pivoting(PivotStrategy::Partial))
+            .collect();
+        let streamed: Vec<f64> = matrices.into_iter().pfaffians().collect();
+        assert_eq!(streamed, expected);
+    }
+}
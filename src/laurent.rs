@@ -0,0 +1,225 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_traits::{One, Zero};
+
+/// A truncated Laurent series ∑ c_k ε^k in a small parameter ε, with f64
+/// coefficients, as it shows up in perturbative expansions.
+///
+/// No terms at or beyond ε^ORDER are ever kept. A series built from finitely
+/// many coefficients is exact until a product first produces a term at
+/// ε^ORDER or beyond and has to drop it, after which it is only known up to
+/// O(ε^p), with `precision()` = Some(p). Sums are known to the smaller of the
+/// two precisions. A product only to the smaller of p_a + v_b and p_b + v_a,
+/// where v is the lowest power present: multiplying by a series that starts
+/// at ε^-2 pulls the unknown O(ε^p) terms of the other factor down to
+/// O(ε^(p-2)). So negative powers in the entries cost precision in the
+/// Pfaffian once anything gets truncated, and ORDER has to leave room for
+/// that; `precision()` on the result says how many coefficients to trust.
+///
+/// This is a `Ring`, so `SkewMatrix<LaurentSeries<ORDER>>::pfaffian()`
+/// expands Pf(A) as a series through the division-free recursion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaurentSeries<const ORDER: i32> {
+    /// The exponent of `coeffs[0]`. Leading zeros are stripped, so for a
+    /// nonzero series this is its valuation.
+    low: i32,
+    coeffs: Vec<f64>,
+    /// None if exact, otherwise the series is known up to O(ε^p).
+    precision: Option<i32>,
+}
+
+impl<const ORDER: i32> LaurentSeries<ORDER> {
+    /// The exact series c_0 ε^low + c_1 ε^(low+1) + ... Any coefficients at
+    /// ε^ORDER or beyond are dropped, leaving it known up to O(ε^ORDER).
+    pub fn new(low: i32, coeffs: Vec<f64>) -> Self {
+        Self::normalized(low, coeffs, None)
+    }
+
+    /// The constant series c.
+    pub fn constant(c: f64) -> Self {
+        Self::new(0, vec![c])
+    }
+
+    /// The series ε.
+    pub fn epsilon() -> Self {
+        Self::new(1, vec![1.0])
+    }
+
+    /// The coefficient of ε^k. Zero outside the stored terms, including at
+    /// or beyond the precision, where the coefficient isn't known.
+    pub fn coeff(&self, k: i32) -> f64 {
+        if k < self.low || self.precision.is_some_and(|p| k >= p) {
+            return 0.0;
+        }
+        self.coeffs
+            .get((k - self.low) as usize)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// The lowest power of ε with a nonzero coefficient, or None if every
+    /// known coefficient is zero.
+    pub fn valuation(&self) -> Option<i32> {
+        (!self.coeffs.is_empty()).then_some(self.low)
+    }
+
+    /// None if the series is exact, or Some(p) if it is only known up to
+    /// O(ε^p).
+    pub fn precision(&self) -> Option<i32> {
+        self.precision
+    }
+
+    /// One past the highest stored exponent.
+    fn end(&self) -> i32 {
+        self.low + self.coeffs.len() as i32
+    }
+
+    /// Drops everything at or beyond ε^ORDER (or the precision, if lower),
+    /// and strips leading and trailing zeros, so equal series compare equal.
+    fn normalized(low: i32, mut coeffs: Vec<f64>, precision: Option<i32>) -> Self {
+        let cut = precision.map_or(ORDER, |p| p.min(ORDER));
+        let keep = (cut - low).max(0) as usize;
+        let truncated = coeffs.iter().skip(keep).any(|&c| c != 0.0);
+        let precision = match precision {
+            None if !truncated => None,
+            _ => Some(cut),
+        };
+        coeffs.truncate(keep);
+
+        let leading = coeffs.iter().take_while(|&&c| c == 0.0).count();
+        coeffs.drain(..leading);
+        while coeffs.last() == Some(&0.0) {
+            coeffs.pop();
+        }
+        let low = match (coeffs.is_empty(), precision) {
+            (false, _) => low + leading as i32,
+            (true, Some(p)) => p,
+            (true, None) => 0,
+        };
+        Self {
+            low,
+            coeffs,
+            precision,
+        }
+    }
+}
+
+/// The smaller of two precisions, None (exact) being infinite.
+fn min_precision(a: Option<i32>, b: Option<i32>) -> Option<i32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+impl<const ORDER: i32> Add for LaurentSeries<ORDER> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let low = self.low.min(rhs.low);
+        let end = self.end().max(rhs.end());
+        let coeffs = (low..end).map(|k| self.coeff(k) + rhs.coeff(k)).collect();
+        Self::normalized(low, coeffs, min_precision(self.precision, rhs.precision))
+    }
+}
+
+impl<const ORDER: i32> Neg for LaurentSeries<ORDER> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            coeffs: self.coeffs.into_iter().map(|c| -c).collect(),
+            ..self
+        }
+    }
+}
+
+impl<const ORDER: i32> Sub for LaurentSeries<ORDER> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl<const ORDER: i32> Mul for LaurentSeries<ORDER> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let exact_zero = |s: &Self| s.coeffs.is_empty() && s.precision.is_none();
+        if exact_zero(&self) || exact_zero(&rhs) {
+            return Self::zero();
+        }
+        // The unknown tail of each factor, times the lowest term of the other.
+        let precision = min_precision(
+            self.precision.map(|p| p + rhs.low),
+            rhs.precision.map(|p| p + self.low),
+        );
+        let mut coeffs = vec![0.0; (self.coeffs.len() + rhs.coeffs.len()).saturating_sub(1)];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] += a * b;
+            }
+        }
+        Self::normalized(self.low + rhs.low, coeffs, precision)
+    }
+}
+
+impl<const ORDER: i32> Zero for LaurentSeries<ORDER> {
+    fn zero() -> Self {
+        Self::new(0, Vec::new())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+}
+
+impl<const ORDER: i32> One for LaurentSeries<ORDER> {
+    fn one() -> Self {
+        Self::constant(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkewMatrix;
+
+    /// Entries α + β ε, in the upper-triangle order (01, 02, 03, 12, 13, 23).
+    const ALPHA: [f64; 6] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    const BETA: [f64; 6] = [0.5, -1.0, 2.0, 1.5, 0.25, -3.0];
+
+    fn linear_entries<const ORDER: i32>() -> SkewMatrix<LaurentSeries<ORDER>> {
+        let values: Vec<LaurentSeries<ORDER>> = ALPHA
+            .iter()
+            .zip(BETA)
+            .map(|(&a, b)| LaurentSeries::new(0, vec![a, b]))
+            .collect();
+        SkewMatrix::from_upper_triangle_generic(4, &values).unwrap()
+    }
+
+    #[test]
+    fn pfaffian_of_linear_series_matches_the_hand_expansion() {
+        let [a01, a02, a03, a12, a13, a23] = ALPHA;
+        let [b01, b02, b03, b12, b13, b23] = BETA;
+        // Pf = x01 x23 - x02 x13 + x03 x12, with x = a + b ε.
+        let c0 = a01 * a23 - a02 * a13 + a03 * a12;
+        let c1 = a01 * b23 + b01 * a23 - a02 * b13 - b02 * a13 + a03 * b12 + b03 * a12;
+        let c2 = b01 * b23 - b02 * b13 + b03 * b12;
+
+        let exact = linear_entries::<3>().pfaffian();
+        assert_eq!(exact.precision(), None);
+        assert_eq!(
+            [exact.coeff(0), exact.coeff(1), exact.coeff(2)],
+            [c0, c1, c2]
+        );
+
+        // At order 2 the ε² terms are dropped, and the result says so.
+        let truncated = linear_entries::<2>().pfaffian();
+        assert_eq!(truncated.precision(), Some(2));
+        assert_eq!([truncated.coeff(0), truncated.coeff(1)], [c0, c1]);
+        assert_eq!(truncated.coeff(2), 0.0);
+    }
+}
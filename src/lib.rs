@@ -0,0 +1,718 @@
+use nalgebra::DMatrix;
+use std::collections::HashMap;
+
+use expansion::{Expansion, NoMemo, PfaffianExpansion, expand_first_row, first_row_pairings};
+
+mod accumulate;
+mod antihermitian;
+#[cfg(feature = "arena")]
+mod arena;
+mod batch;
+mod builder;
+mod cached;
+mod calculus;
+mod canonical;
+mod classify;
+mod cofactor;
+mod compare;
+#[cfg(feature = "disk-cache")]
+mod disk_cache;
+mod distributed;
+mod divide;
+mod dot;
+mod dual;
+mod elimination;
+mod engine;
+mod ensemble;
+mod error;
+mod expansion;
+mod fixed;
+mod gf2;
+mod grassmann;
+mod hafnian;
+#[cfg(feature = "half")]
+mod half;
+mod integer;
+mod io;
+mod iter;
+mod laurent;
+mod lazy;
+mod lowrank;
+mod ltl;
+mod lu;
+mod matchings;
+mod mixed;
+mod overlap;
+mod packed;
+mod padic;
+mod plan;
+mod polynomial;
+#[cfg(feature = "rand")]
+mod random;
+mod raw;
+mod reduction;
+mod refine;
+mod ring;
+mod robust;
+#[cfg(feature = "rand")]
+mod sampling;
+mod sign;
+mod stats;
+#[cfg(feature = "rand")]
+mod stochastic;
+mod subsets;
+mod sweep;
+mod toeplitz;
+mod tournament;
+mod transform;
+mod tropical;
+#[cfg(feature = "rand")]
+mod tutte;
+mod units;
+mod update;
+
+pub use accumulate::{PartitionFunction, PfaffianProduct};
+pub use antihermitian::AntiHermitianMatrix;
+pub use batch::{pfaffian_batch, pfaffian_block_diagonal};
+pub use builder::SkewMatrixBuilder;
+pub use cached::CachedSkewMatrix;
+pub use canonical::rotation_generator_pfaffian;
+pub use classify::{PfaffianResult, ZeroReason};
+pub use compare::assert_skew_matrices_close;
+#[cfg(feature = "disk-cache")]
+pub use disk_cache::DiskCache;
+pub use distributed::PartialFactorization;
+pub use dual::ComplexDual;
+pub use elimination::{PivotStep, PivotStrategy};
+pub use engine::PfaffianEngine;
+pub use ensemble::{expected_pfaffian, expected_pfaffian_squared};
+pub use error::PfaffianError;
+pub use fixed::{Dim, EvenDim, SkewMatrix2, SkewMatrix4, SkewMatrix6, SkewMatrix8, SkewMatrixN};
+pub use gf2::Gf2Mod;
+pub use hafnian::{hafnian, hafnian_generic};
+pub use integer::{Budget, pfaffian_crt, pfaffian_i128, pfaffian_mod_p};
+pub use io::{MatrixFormat, pfaffian_dir, read_matrix};
+pub use iter::{PfaffianIteratorExt, Pfaffians};
+pub use laurent::LaurentSeries;
+pub use lazy::LazySkewMatrix;
+pub use lowrank::LowRankSkewMatrix;
+pub use ltl::SkewFactorization;
+pub use lu::pfaffian_from_lu;
+pub use matchings::{MatchingStats, Matchings};
+pub use overlap::{fermionic_overlap, gram_pfaffian, overlap_pfaffian};
+pub use packed::{PackedSkewMatrix, pfaffian_upper};
+pub use padic::PAdic;
+pub use plan::SparsityPlan;
+pub use polynomial::Polynomial;
+#[cfg(feature = "rand")]
+pub use random::random_with_pfaffian;
+pub use raw::{RawMatrix, pfaffian_of_skew_part};
+pub use reduction::DeterministicReduction;
+pub use ring::{Ring, Semiring};
+pub use sign::{Sign, SignResult, SignTracker, pfaffian_sign_rigorous};
+pub use stats::PfaffianStats;
+pub use sweep::pfaffian_polynomial;
+pub use toeplitz::ToeplitzSkewMatrix;
+pub use transform::{block_circulant, pfaffian_block_circulant};
+pub use tropical::MaxPlus;
+#[cfg(feature = "rand")]
+pub use tutte::tutte_matrix_has_matching;
+pub use units::{Quantity, UnitSkewMatrixN};
+
+/// A struct to hold our skew-symmetric matrix.
+/// We use a DMatrix (dynamic matrix) from nalgebra.
+///
+/// The entries are f64 by default, and everything numerical (elimination,
+/// inverses, gradients, ...) is only defined for f64. The recursive
+/// `pfaffian()` never divides, so it also works over any commutative `Ring`,
+/// e.g. polynomials or exact rationals.
+pub struct SkewMatrix<T = f64> {
+    data: DMatrix<T>,
+}
+
+impl<T: Ring> SkewMatrix<T> {
+    /// Wraps a matrix the caller already knows is skew-symmetric and even-sized.
+    pub(crate) fn from_skew_unchecked(data: DMatrix<T>) -> Self {
+        Self { data }
+    }
+
+    /// Creates a new SkewMatrix over any ring from a list of upper-triangular
+    /// values, laid out as in `from_upper_triangle`.
+    pub fn from_upper_triangle_generic(n: usize, values: &[T]) -> Result<Self, PfaffianError> {
+        if !n.is_multiple_of(2) {
+            return Err(PfaffianError::OddDimension { n });
+        }
+        // Written with saturating_sub so the degenerate n = 0 case works.
+        let expected_vals = n * n.saturating_sub(1) / 2;
+        if values.len() != expected_vals {
+            return Err(PfaffianError::WrongValueCount {
+                n,
+                expected: expected_vals,
+                got: values.len(),
+            });
+        }
+
+        let mut m = DMatrix::<T>::zeros(n, n);
+        let positions = (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j)));
+
+        for ((i, j), val) in positions.zip(values) {
+            m[(i, j)] = val.clone();
+            m[(j, i)] = -val.clone();
+        }
+        Ok(Self::from_skew_unchecked(m))
+    }
+
+    /// Borrows the underlying dense matrix, e.g. for further nalgebra operations.
+    pub fn as_matrix(&self) -> &DMatrix<T> {
+        &self.data
+    }
+
+    /// Consumes the SkewMatrix and hands back the underlying dense matrix.
+    pub fn into_matrix(self) -> DMatrix<T> {
+        self.data
+    }
+
+    /// Sets A[i][j] = value and A[j][i] = -value. Panics if i == j or either
+    /// index is out of range.
+    pub fn set(&mut self, i: usize, j: usize, value: T) {
+        assert!(i != j, "The diagonal of a skew matrix is always zero.");
+        self.data[(i, j)] = value.clone();
+        self.data[(j, i)] = -value;
+    }
+
+    /// Recursively computes the Pfaffian of the matrix.
+    /// This implementation is for demonstration and is not O(n^3).
+    /// It directly models the "sum over perfect matchings" definition.
+    ///
+    /// The formula is: Pf(A) = sum_{j=2..2n} (-1)^j * A_{1,j} * Pf(A_{1,j})
+    ///
+    /// Pf(A_ij) is the pfaffian of the submatrix with rows/cols i and j removed.
+    ///
+    /// Nothing is cached, so each call redoes the recursion. Wrap the matrix
+    /// in a `CachedSkewMatrix` to compute it once.
+    pub fn pfaffian(&self) -> T {
+        // Use a memoization table (HashMap) to store results for subproblems.
+        // This turns the exponential O(n!!) recursion into a fast O(n^3)
+        // dynamic programming algorithm. This is one way to get the "magic" speedup.
+        let mut memo: HashMap<Vec<usize>, T> = HashMap::new();
+        let initial_indices: Vec<usize> = (0..self.data.nrows()).collect();
+        let mut expansion = PfaffianExpansion { data: &self.data };
+        expand_first_row(&mut expansion, &initial_indices, &mut memo)
+    }
+
+    /// Like `pfaffian()`, but writes the result into `out` instead of
+    /// returning it.
+    ///
+    /// The subproblems are expanded as usual, but the top-level terms
+    /// A_0j · Pf(minor) are summed straight into `out`, so the result is
+    /// never built as a separate value, copied into `out` or kept in the
+    /// memo table. Whether `out`'s own allocation survives is up to T's
+    /// in-place `+`/`-`. `CachedSkewMatrix::pfaffian_into_out` copies
+    /// straight out of its cache instead.
+    pub fn pfaffian_into_out(&self, out: &mut T) {
+        let indices: Vec<usize> = (0..self.data.nrows()).collect();
+        if indices.is_empty() {
+            out.set_one();
+            return;
+        }
+        out.set_zero();
+        let mut memo: HashMap<Vec<usize>, T> = HashMap::new();
+        let mut expansion = PfaffianExpansion { data: &self.data };
+        for (j, sign, rest) in first_row_pairings(&indices) {
+            let sub = expand_first_row(&mut expansion, &rest, &mut memo);
+            expansion.add_term(out, 0, j, sign > 0.0, sub);
+        }
+    }
+}
+
+impl SkewMatrix {
+    /// The 0x0 matrix. Its Pfaffian is 1 (the empty product), which is what
+    /// makes identities like Pf(A ⊕ B) = Pf(A) Pf(B) hold with an empty block.
+    pub fn empty() -> Self {
+        Self::from_skew_unchecked(DMatrix::<f64>::zeros(0, 0))
+    }
+
+    /// Creates a new SkewMatrix from a list of upper-triangular values.
+    /// For a 4x4 matrix, you'd provide 6 values: (a, b, c, d, e, f)
+    /// which map to:
+    ///   0  a  b  c
+    ///  -a  0  d  e
+    ///  -b -d  0  f
+    ///  -c -e -f  0
+    ///
+    /// NaN and infinite values are rejected with `PfaffianError::NonFinite`,
+    /// since they would only produce a garbage Pfaffian. Use
+    /// `from_upper_triangle_allow_non_finite` if you really want them.
+    pub fn from_upper_triangle(n: usize, values: &[f64]) -> Result<Self, PfaffianError> {
+        if let Some(index) = values.iter().position(|v| !v.is_finite()) {
+            return Err(PfaffianError::NonFinite { index });
+        }
+        Self::from_upper_triangle_allow_non_finite(n, values)
+    }
+
+    /// Like `from_upper_triangle`, but lets NaN and infinite values through.
+    pub fn from_upper_triangle_allow_non_finite(
+        n: usize,
+        values: &[f64],
+    ) -> Result<Self, PfaffianError> {
+        Self::from_upper_triangle_generic(n, values)
+    }
+
+    /// Creates a new SkewMatrix from a dense matrix, checking that it is square,
+    /// even-sized and skew-symmetric to within `tol`.
+    /// The stored matrix is the exact antisymmetric part (A - Aᵀ) / 2, so small
+    /// rounding errors in the input don't leak into the Pfaffian. Where
+    /// A_ij - A_ji would overflow, for entries beyond f64::MAX / 2, the two
+    /// are halved before subtracting instead.
+    pub fn from_matrix(data: DMatrix<f64>, tol: f64) -> Result<Self, PfaffianError> {
+        let (rows, cols) = data.shape();
+        if rows != cols {
+            return Err(PfaffianError::NotSquare { rows, cols });
+        }
+        if !rows.is_multiple_of(2) {
+            return Err(PfaffianError::OddDimension { n: rows });
+        }
+        for i in 0..rows {
+            for j in i..rows {
+                if (data[(i, j)] + data[(j, i)]).abs() > tol {
+                    return Err(PfaffianError::NotSkewSymmetric { row: i, col: j });
+                }
+            }
+        }
+
+        let skew = data.zip_map(&data.transpose(), |a, b| {
+            let diff = a - b;
+            if diff.is_finite() {
+                diff * 0.5
+            } else {
+                a * 0.5 - b * 0.5
+            }
+        });
+        Ok(Self::from_skew_unchecked(skew))
+    }
+
+    /// Like `from_matrix`, from the n * n entries of a flat column-major
+    /// buffer (Fortran order, and nalgebra's own layout). The buffer is
+    /// copied as is, with no transposition.
+    pub fn from_column_major(n: usize, data: &[f64], tol: f64) -> Result<Self, PfaffianError> {
+        if data.len() != n * n {
+            return Err(PfaffianError::WrongValueCount {
+                n,
+                expected: n * n,
+                got: data.len(),
+            });
+        }
+        Self::from_matrix(DMatrix::from_column_slice(n, n, data), tol)
+    }
+
+    /// Like `from_matrix`, from the n * n entries of a flat row-major
+    /// buffer (C order).
+    pub fn from_row_major(n: usize, data: &[f64], tol: f64) -> Result<Self, PfaffianError> {
+        if data.len() != n * n {
+            return Err(PfaffianError::WrongValueCount {
+                n,
+                expected: n * n,
+                got: data.len(),
+            });
+        }
+        Self::from_matrix(DMatrix::from_row_slice(n, n, data), tol)
+    }
+
+    /// Builds the skew matrix from the commutator [X, Y] = XY - YX, projected
+    /// onto its antisymmetric part: ([X, Y] - [X, Y]ᵀ) / 2.
+    ///
+    /// The commutator of two skew (or two symmetric) matrices is already
+    /// skew, so there the projection changes nothing. For general X and Y it
+    /// drops the symmetric part, (XY - YX + YᵀXᵀ - XᵀYᵀ) / 2. Fails if X and Y
+    /// aren't square matrices of the same even size.
+    pub fn from_commutator(x: &DMatrix<f64>, y: &DMatrix<f64>) -> Result<Self, PfaffianError> {
+        if x.shape() != y.shape() {
+            return Err(PfaffianError::ShapeMismatch {
+                left: x.shape(),
+                right: y.shape(),
+            });
+        }
+        let (rows, cols) = x.shape();
+        if rows != cols {
+            return Err(PfaffianError::NotSquare { rows, cols });
+        }
+        // from_matrix stores exactly the antisymmetric part, and with an
+        // infinite tolerance it only checks the size.
+        Self::from_matrix(x * y - y * x, f64::INFINITY)
+    }
+
+    /// Builds the contracted skew matrix L · C · R, as it comes out of
+    /// contracting environment tensors L and R around a skew core C.
+    ///
+    /// The result is only skew when R = Lᵀ (up to rounding), so it is
+    /// checked to within 1e-9 of ‖L‖ ‖C‖ ‖R‖, which bounds the size of the
+    /// product. For square L the congruence identity then gives
+    /// Pf(L C Lᵀ) = det(L) Pf(C). Fails if the shapes don't chain, or if
+    /// the product isn't skew or even-sized.
+    pub fn from_contraction(
+        left: &DMatrix<f64>,
+        core: &SkewMatrix,
+        right: &DMatrix<f64>,
+    ) -> Result<Self, PfaffianError> {
+        if left.ncols() != core.data.nrows() {
+            return Err(PfaffianError::ShapeMismatch {
+                left: left.shape(),
+                right: core.data.shape(),
+            });
+        }
+        if right.nrows() != core.data.ncols() || right.ncols() != left.nrows() {
+            return Err(PfaffianError::ShapeMismatch {
+                left: left.shape(),
+                right: right.shape(),
+            });
+        }
+        let tol = 1e-9 * left.norm() * core.data.norm() * right.norm();
+        Self::from_matrix(left * &core.data * right, tol)
+    }
+
+    /// Builds A_ij = k(x_i, x_j) from an antisymmetric kernel k over a set of
+    /// points, as in spatial statistics.
+    ///
+    /// k is assumed antisymmetric, k(x, y) = -k(y, x), and only evaluated
+    /// for i < j, so the kernel runs n(n-1)/2 times and the lower triangle
+    /// is always the exact negative. Fails like `from_upper_triangle` for an
+    /// odd number of points or a non-finite kernel value.
+    pub fn from_points<P>(
+        points: &[P],
+        kernel: impl Fn(&P, &P) -> f64,
+    ) -> Result<Self, PfaffianError> {
+        let values: Vec<f64> = points
+            .iter()
+            .enumerate()
+            .flat_map(|(i, x)| points[i + 1..].iter().map(|y| kernel(x, y)))
+            .collect();
+        Self::from_upper_triangle(points.len(), &values)
+    }
+
+    /// Computes the Pfaffian by the plain "sum over perfect matchings"
+    /// recursion, with no memoization at all.
+    ///
+    /// This is the definition exactly as written above `pfaffian()`, so it is
+    /// useful for teaching and for cross-checking the memoized version. It
+    /// visits all (n-1)!! matchings, which is 2,027,025 already at n = 16,
+    /// so keep n small.
+    pub fn pfaffian_naive(&self) -> f64 {
+        let indices: Vec<usize> = (0..self.data.nrows()).collect();
+        let mut expansion = PfaffianExpansion { data: &self.data };
+        expand_first_row(&mut expansion, &indices, &mut NoMemo)
+    }
+
+    /// Computes the Pfaffian straight from its textbook definition, as a sum
+    /// over all n! permutations σ:
+    ///
+    ///    Pf(A) = 1 / (2^m m!) ∑_σ sgn(σ) ∏_{k<m} A[σ(2k)][σ(2k+1)],  n = 2m
+    ///
+    /// Each perfect matching shows up 2^m m! times (swap within a pair, or
+    /// reorder the pairs), hence the normalization. This is the ground
+    /// truth the other algorithms are checked against, and nothing else:
+    /// n = 8 is already 40,320 permutations and n = 12 nearly 500 million.
+    pub fn pfaffian_bruteforce(&self) -> f64 {
+        let n = self.data.nrows();
+        let m = n / 2;
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut sign = 1.0;
+        let term = |perm: &[usize]| -> f64 {
+            (0..m)
+                .map(|k| self.data[(perm[2 * k], perm[2 * k + 1])])
+                .product()
+        };
+
+        // Heap's algorithm: every step is a single transposition, so the
+        // sign of the permutation just flips each time.
+        let mut total = term(&perm);
+        let mut counters = vec![0; n];
+        let mut i = 1;
+        while i < n {
+            if counters[i] < i {
+                let j = if i % 2 == 1 { counters[i] } else { 0 };
+                perm.swap(j, i);
+                sign = -sign;
+                total += sign * term(&perm);
+                counters[i] += 1;
+                i = 1;
+            } else {
+                counters[i] = 0;
+                i += 1;
+            }
+        }
+
+        let normalization: f64 = (1..=m).map(|k| 2.0 * k as f64).product();
+        total / normalization
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pfaffian_of_4x4_takes_a_plus_sign_on_the_first_pairing() {
+        // Pf = a01 a23 - a02 a13 + a03 a12 = 1·6 - 2·5 + 3·4.
+        let matrix = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        assert_eq!(matrix.pfaffian(), 8.0);
+        assert_eq!(matrix.pfaffian_naive(), 8.0);
+    }
+
+    #[test]
+    fn into_matrix_round_trips_through_from_matrix() {
+        let values = [1.0, -2.0, 0.5, 4.0, 3.0, -6.0];
+        let matrix = SkewMatrix::from_upper_triangle(4, &values).unwrap();
+        let dense = matrix.as_matrix().clone();
+        assert_eq!(dense[(0, 1)], 1.0);
+        assert_eq!(dense[(1, 0)], -1.0);
+
+        let back = SkewMatrix::from_matrix(matrix.into_matrix(), 0.0).unwrap();
+        assert_eq!(back.as_matrix(), &dense);
+    }
+
+    #[test]
+    fn non_finite_entries_are_rejected_unless_allowed() {
+        let nan = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, f64::NAN, 4.0, 5.0, 6.0]);
+        assert!(matches!(nan, Err(PfaffianError::NonFinite { index: 2 })));
+        let inf = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, f64::INFINITY]);
+        assert!(matches!(inf, Err(PfaffianError::NonFinite { index: 5 })));
+
+        let allowed = SkewMatrix::from_upper_triangle_allow_non_finite(
+            4,
+            &[1.0, 2.0, f64::NAN, 4.0, 5.0, 6.0],
+        );
+        assert!(allowed.unwrap().pfaffian().is_nan());
+    }
+
+    #[test]
+    fn empty_matrix_has_pfaffian_one() {
+        let built = SkewMatrix::from_upper_triangle(0, &[]).unwrap();
+        for matrix in [SkewMatrix::empty(), built] {
+            assert_eq!(matrix.as_matrix().nrows(), 0);
+            assert_eq!(matrix.pfaffian(), 1.0);
+            assert_eq!(matrix.pfaffian_naive(), 1.0);
+            assert_eq!(matrix.pfaffian_bruteforce(), 1.0);
+            assert_eq!(matrix.pfaffian_with_pivoting(PivotStrategy::Partial), 1.0);
+        }
+    }
+
+    #[test]
+    fn naive_enumeration_agrees_with_memoized_pfaffian_up_to_n_8() {
+        for n in (0..=8usize).step_by(2) {
+            let values: Vec<f64> = (0..n * n.saturating_sub(1) / 2)
+                .map(|k| ((k * 3 + 1) as f64).sin())
+                .collect();
+            let matrix = SkewMatrix::from_upper_triangle(n, &values).unwrap();
+            let (naive, memoized) = (matrix.pfaffian_naive(), matrix.pfaffian());
+            assert!((naive - memoized).abs() < 1e-12, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn pfaffian_into_out_overwrites_a_preallocated_big_rational() {
+        use num_bigint::BigInt;
+        use num_rational::BigRational;
+
+        let r = |p: i64, q: i64| BigRational::new(BigInt::from(p), BigInt::from(q));
+        let values = [r(1, 2), r(2, 3), r(3, 4), r(4, 5), r(5, 6), r(6, 7)];
+        let matrix = SkewMatrix::from_upper_triangle_generic(4, &values).unwrap();
+
+        // 1/2 · 6/7 - 2/3 · 5/6 + 3/4 · 4/5 = 3/7 - 5/9 + 3/5
+        let mut out = BigRational::from_integer(BigInt::from(10).pow(100));
+        matrix.pfaffian_into_out(&mut out);
+        assert_eq!(out, r(3, 7) - r(5, 9) + r(3, 5));
+        assert_eq!(out, matrix.pfaffian());
+    }
+
+    thread_local! {
+        static CLONES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    /// An integer that counts how often it is cloned, to tell whether a
+    /// whole result gets copied.
+    #[derive(Debug, PartialEq)]
+    struct Counted(i64);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.set(CLONES.get() + 1);
+            Counted(self.0)
+        }
+    }
+
+    impl std::ops::Add for Counted {
+        type Output = Self;
+        fn add(self, rhs: Self) -> Self {
+            Counted(self.0 + rhs.0)
+        }
+    }
+
+    impl std::ops::Sub for Counted {
+        type Output = Self;
+        fn sub(self, rhs: Self) -> Self {
+            Counted(self.0 - rhs.0)
+        }
+    }
+
+    impl std::ops::Mul for Counted {
+        type Output = Self;
+        fn mul(self, rhs: Self) -> Self {
+            Counted(self.0 * rhs.0)
+        }
+    }
+
+    impl std::ops::Neg for Counted {
+        type Output = Self;
+        fn neg(self) -> Self {
+            Counted(-self.0)
+        }
+    }
+
+    impl num_traits::Zero for Counted {
+        fn zero() -> Self {
+            Counted(0)
+        }
+        fn is_zero(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    impl num_traits::One for Counted {
+        fn one() -> Self {
+            Counted(1)
+        }
+    }
+
+    #[test]
+    fn pfaffian_into_out_never_copies_the_result() {
+        let values: Vec<Counted> = (1..=15).map(Counted).collect();
+        let matrix = SkewMatrix::from_upper_triangle_generic(6, &values).unwrap();
+
+        CLONES.set(0);
+        let by_value = matrix.pfaffian();
+        let returning = CLONES.get();
+
+        CLONES.set(0);
+        let mut out = Counted(7);
+        matrix.pfaffian_into_out(&mut out);
+        assert_eq!(out, by_value);
+        // `pfaffian()` clones its result once, into the memo table; summing
+        // into `out` skips that, and copying a by-value result in would
+        // have cost one more.
+        assert_eq!(CLONES.get(), returning - 1);
+
+        let mut out = Counted(7);
+        SkewMatrix::<Counted>::from_upper_triangle_generic(0, &[])
+            .unwrap()
+            .pfaffian_into_out(&mut out);
+        assert_eq!(out, Counted(1));
+    }
+
+    #[test]
+    fn commutator_is_projected_onto_a_skew_matrix() {
+        let x = DMatrix::from_fn(4, 4, |i, j| ((3 * i + j) % 5) as f64 - 2.0);
+        let y = DMatrix::from_fn(4, 4, |i, j| ((i * j + 2 * i + 1) % 7) as f64 / 2.0);
+        let matrix = SkewMatrix::from_commutator(&x, &y).unwrap();
+        let a = matrix.as_matrix();
+        assert_eq!(a, &-a.transpose());
+
+        let c = &x * &y - &y * &x;
+        let s = (&c - c.transpose()) / 2.0;
+        assert_eq!(a, &s);
+        let direct = s[(0, 1)] * s[(2, 3)] - s[(0, 2)] * s[(1, 3)] + s[(0, 3)] * s[(1, 2)];
+        assert!((matrix.pfaffian() - direct).abs() < 1e-12);
+
+        assert!(matches!(
+            SkewMatrix::from_commutator(&x, &DMatrix::zeros(2, 2)),
+            Err(PfaffianError::ShapeMismatch { .. })
+        ));
+        let odd = DMatrix::<f64>::identity(3, 3);
+        assert!(matches!(
+            SkewMatrix::from_commutator(&odd, &odd),
+            Err(PfaffianError::OddDimension { n: 3 })
+        ));
+    }
+
+    #[test]
+    fn pfaffian_of_a_contraction_is_det_l_times_pf_core() {
+        let core = SkewMatrix::from_upper_triangle(4, &[1.0, -2.0, 0.5, 3.0, 1.5, -1.0]).unwrap();
+        let left = DMatrix::from_fn(4, 4, |i, j| ((2 * i + 3 * j) % 5) as f64 - 1.5);
+        let matrix = SkewMatrix::from_contraction(&left, &core, &left.transpose()).unwrap();
+        let expected = left.determinant() * core.pfaffian();
+        assert!((matrix.pfaffian() - expected).abs() < 1e-9 * expected.abs());
+
+        assert!(matches!(
+            SkewMatrix::from_contraction(&left, &core, &left),
+            Err(PfaffianError::NotSkewSymmetric { .. })
+        ));
+        assert!(matches!(
+            SkewMatrix::from_contraction(&DMatrix::zeros(4, 2), &core, &left),
+            Err(PfaffianError::ShapeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn column_major_and_row_major_construction_agree() {
+        let values: Vec<f64> = (1..=15).map(|k| (k as f64).ln() - 1.0).collect();
+        let dense = SkewMatrix::from_upper_triangle(6, &values)
+            .unwrap()
+            .into_matrix();
+        let column_major = dense.as_slice().to_vec();
+        let row_major = dense.transpose().as_slice().to_vec();
+
+        let from_columns = SkewMatrix::from_column_major(6, &column_major, 0.0).unwrap();
+        let from_rows = SkewMatrix::from_row_major(6, &row_major, 0.0).unwrap();
+        assert_eq!(from_columns.as_matrix(), &dense);
+        assert_eq!(from_rows.as_matrix(), &dense);
+
+        // The same buffer read the other way round is the transpose, -A.
+        let swapped = SkewMatrix::from_row_major(6, &column_major, 0.0).unwrap();
+        assert_eq!(swapped.as_matrix(), &-&dense);
+        assert!(matches!(
+            SkewMatrix::from_column_major(6, &column_major[1..], 0.0),
+            Err(PfaffianError::WrongValueCount {
+                expected: 36,
+                got: 35,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn recursion_and_elimination_agree_with_the_permutation_sum() {
+        for n in [2, 4, 6, 8usize] {
+            let values: Vec<f64> = (0..n * (n - 1) / 2)
+                .map(|k| ((k * 5 + 2) as f64).cos() * 2.0)
+                .collect();
+            let matrix = SkewMatrix::from_upper_triangle(n, &values).unwrap();
+            let exact = matrix.pfaffian_bruteforce();
+            assert!(exact.abs() > 1e-3, "n = {}", n);
+            for pf in [
+                matrix.pfaffian(),
+                matrix.pfaffian_with_pivoting(PivotStrategy::Partial),
+            ] {
+                assert!((pf - exact).abs() < 1e-10 * exact.abs(), "n = {}", n);
+            }
+        }
+    }
+
+    #[test]
+    fn difference_kernel_on_1d_points() {
+        let kernel = |x: &f64, y: &f64| x - y;
+        let pair = SkewMatrix::from_points(&[3.0, 0.5], kernel).unwrap();
+        assert_eq!(pair.pfaffian(), 2.5);
+
+        // x 1ᵀ - 1 xᵀ has rank 2, so with four or more points Pf = 0:
+        // (x0 - x1)(x2 - x3) - (x0 - x2)(x1 - x3) + (x0 - x3)(x1 - x2) = 0.
+        let points = [0.0, 1.0, 3.0, 7.0];
+        let matrix = SkewMatrix::from_points(&points, kernel).unwrap();
+        assert_eq!(matrix.as_matrix()[(1, 3)], -6.0);
+        assert_eq!(matrix.as_matrix()[(3, 1)], 6.0);
+        assert_eq!(matrix.pfaffian(), 0.0);
+
+        assert!(matches!(
+            SkewMatrix::from_points(&points[..3], kernel),
+            Err(PfaffianError::OddDimension { n: 3 })
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use std::cmp::Ordering;
+
+use nalgebra::DMatrix;
+
+use crate::{PfaffianError, PivotStrategy, SkewMatrix};
+
+/// A skew matrix in outer-product form A = U J Uᵀ, where U is n x 2r and J
+/// is the 2r x 2r standard symplectic form (r blocks [[0, 1], [-1, 0]]).
+///
+/// Only U is stored, n x 2r instead of n x n.
+pub struct LowRankSkewMatrix {
+    u: DMatrix<f64>,
+    rank_blocks: usize,
+}
+
+impl LowRankSkewMatrix {
+    /// Wraps the factor U, with r = U.ncols() / 2. Fails if U has an odd
+    /// number of rows or of columns.
+    pub fn new(u: DMatrix<f64>) -> Result<Self, PfaffianError> {
+        let (rows, cols) = u.shape();
+        if !rows.is_multiple_of(2) {
+            return Err(PfaffianError::OddDimension { n: rows });
+        }
+        if !cols.is_multiple_of(2) {
+            return Err(PfaffianError::OddDimension { n: cols });
+        }
+        Ok(Self {
+            u,
+            rank_blocks: cols / 2,
+        })
+    }
+
+    /// Forms the dense n x n matrix U J Uᵀ.
+    pub fn to_dense(&self) -> SkewMatrix {
+        // U J has columns (u_1, -u_0, u_3, -u_2, ...), so U J Uᵀ is the sum
+        // over blocks k of u_2k u_(2k+1)ᵀ - u_(2k+1) u_2kᵀ.
+        let n = self.u.nrows();
+        let mut data = DMatrix::<f64>::zeros(n, n);
+        for k in 0..self.rank_blocks {
+            let (a, b) = (self.u.column(2 * k), self.u.column(2 * k + 1));
+            data.ger(1.0, &a, &b, 1.0);
+            data.ger(-1.0, &b, &a, 1.0);
+        }
+        SkewMatrix::from_skew_unchecked(data)
+    }
+
+    /// Computes Pf(U J Uᵀ), without forming it when 2r ≤ n.
+    ///
+    /// The rank of U J Uᵀ is at most 2r, so for 2r < n the matrix is
+    /// singular and the Pfaffian is exactly 0, in O(1). For 2r = n, U is
+    /// square and the congruence identity gives Pf(U J Uᵀ) = det(U) Pf(J) =
+    /// det(U), one LU of the n x 2r factor, O(n r^2). Only when 2r > n is
+    /// there no shortcut, and then the n x n matrix, smaller than U, is
+    /// formed and eliminated.
+    pub fn pfaffian(&self) -> f64 {
+        let n = self.u.nrows();
+        match (2 * self.rank_blocks).cmp(&n) {
+            Ordering::Less => 0.0,
+            Ordering::Equal => self.u.clone().lu().determinant(),
+            Ordering::Greater => self
+                .to_dense()
+                .pfaffian_with_pivoting(PivotStrategy::Partial),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factor(n: usize, cols: usize) -> DMatrix<f64> {
+        DMatrix::from_fn(n, cols, |i, j| {
+            ((i * i * 7 + j * j * 3 + i * j) as f64).sin()
+        })
+    }
+
+    #[test]
+    fn low_rank_pfaffian_matches_the_dense_matrix() {
+        // 2r = n: Pf(U J Uᵀ) = det(U).
+        let square = LowRankSkewMatrix::new(factor(6, 6)).unwrap();
+        let dense = square
+            .to_dense()
+            .pfaffian_with_pivoting(PivotStrategy::Partial);
+        assert!(dense.abs() > 1e-3);
+        assert!((square.pfaffian() - dense).abs() < 1e-10 * dense.abs());
+
+        // 2r > n: no shortcut, but the same answer.
+        let wide = LowRankSkewMatrix::new(factor(4, 8)).unwrap();
+        let dense = wide.to_dense().pfaffian();
+        assert!((wide.pfaffian() - dense).abs() < 1e-10 * dense.abs().max(1.0));
+
+        // 2r < n: singular, and the dense elimination agrees up to rounding.
+        let thin = LowRankSkewMatrix::new(factor(8, 4)).unwrap();
+        assert_eq!(thin.pfaffian(), 0.0);
+        let dense = thin.to_dense();
+        assert!(dense.pfaffian_with_pivoting(PivotStrategy::Partial).abs() < 1e-10);
+
+        assert!(LowRankSkewMatrix::new(factor(8, 3)).is_err());
+        assert!(LowRankSkewMatrix::new(factor(5, 2)).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use nalgebra::{DMatrix, DVector};
+
+use crate::SkewMatrix;
+
+/// The skew LTLᵀ decomposition Pᵀ A P = L T Lᵀ, where P is a permutation,
+/// L is unit lower triangular and T is skew-symmetric and tridiagonal.
+///
+/// Unlike `pfaffian_with_pivoting`, which only needs every other column
+/// eliminated, this eliminates every column, so that T comes out fully
+/// tridiagonal and the factors can be reused: the Pfaffian, solves and
+/// the inverse all read from one O(n^3) factorization.
+pub struct SkewFactorization {
+    /// Row i of PᵀAP is row perm[i] of A.
+    pub(crate) perm: Vec<usize>,
+    /// det(P), i.e. ±1 from the number of swaps.
+    pub(crate) perm_sign: f64,
+    pub(crate) l: DMatrix<f64>,
+    pub(crate) t: DMatrix<f64>,
+}
+
+impl SkewFactorization {
+    /// Pf(A) = det(P) · Pf(T), and Pf of a tridiagonal T is the product of
+    /// its (k, k+1) entries for even k. det(L) = 1, so L drops out.
+    pub fn pfaffian(&self) -> f64 {
+        let n = self.t.nrows();
+        self.perm_sign
+            * (0..n)
+                .step_by(2)
+                .map(|k| self.t[(k, k + 1)])
+                .product::<f64>()
+    }
+
+    /// ln|Pf(A)| together with the sign of Pf(A), as (sign, ln|Pf|), in the
+    /// same convention as `SkewMatrix::log_pfaffian`: a singular matrix gives
+    /// (0.0, -inf).
+    pub fn log_pfaffian(&self) -> (f64, f64) {
+        let mut sign = self.perm_sign;
+        let mut log_abs = 0.0;
+        for k in (0..self.t.nrows()).step_by(2) {
+            let p = self.t[(k, k + 1)];
+            if p == 0.0 {
+                return (0.0, f64::NEG_INFINITY);
+            }
+            sign *= p.signum();
+            log_abs += p.abs().ln();
+        }
+        (sign, log_abs)
+    }
+
+    /// Solves A x = b, or returns None if A is singular.
+    ///
+    /// A = P L T Lᵀ Pᵀ, so this is two triangular solves around a solve
+    /// with the tridiagonal T, which is O(n^2) in all. Panics if b doesn't
+    /// have n entries.
+    pub fn solve(&self, b: &DVector<f64>) -> Option<DVector<f64>> {
+        let n = self.t.nrows();
+        assert_eq!(b.len(), n, "The right-hand side must have n entries.");
+        let pb = DVector::from_fn(n, |i, _| b[self.perm[i]]);
+        let y = self.l.solve_lower_triangular(&pb)?;
+        let z = self.solve_tridiagonal(&y)?;
+        let w = self.l.transpose().solve_upper_triangular(&z)?;
+        let mut x = DVector::zeros(n);
+        for (i, &p) in self.perm.iter().enumerate() {
+            x[p] = w[i];
+        }
+        Some(x)
+    }
+
+    /// Computes A⁻¹ one column at a time with `solve`, or returns None if A
+    /// is singular.
+    pub fn inverse(&self) -> Option<DMatrix<f64>> {
+        let n = self.t.nrows();
+        let mut inverse = DMatrix::zeros(n, n);
+        for c in 0..n {
+            let x = self.solve(&DVector::from_fn(n, |i, _| if i == c { 1.0 } else { 0.0 }))?;
+            inverse.set_column(c, &x);
+        }
+        Some(inverse)
+    }
+
+    /// Solves T z = y for the skew tridiagonal T, with α_k = T[k][k+1].
+    ///
+    /// Row k reads -α_{k-1} z_{k-1} + α_k z_{k+1} = y_k. The even rows give
+    /// the odd z going forwards and the odd rows give the even z going
+    /// backwards, dividing only by the α_k with k even, which are exactly
+    /// the pivots in Pf(T). So T is singular iff one of them is zero.
+    fn solve_tridiagonal(&self, y: &DVector<f64>) -> Option<DVector<f64>> {
+        let n = self.t.nrows();
+        let alpha = |k: usize| self.t[(k, k + 1)];
+        if (0..n).step_by(2).any(|k| alpha(k) == 0.0) {
+            return None;
+        }
+
+        let mut z = DVector::zeros(n);
+        for k in (0..n).step_by(2) {
+            let behind = if k > 0 { alpha(k - 1) * z[k - 1] } else { 0.0 };
+            z[k + 1] = (y[k] + behind) / alpha(k);
+        }
+        for k in (1..n).step_by(2).rev() {
+            let ahead = if k + 1 < n { alpha(k) * z[k + 1] } else { 0.0 };
+            z[k - 1] = (ahead - y[k]) / alpha(k - 1);
+        }
+        Some(z)
+    }
+}
+
+impl SkewMatrix {
+    /// Computes the LTLᵀ decomposition with partial pivoting (Wimmer's
+    /// skew_LTL from PFAPACK), for reuse across several queries.
+    pub fn factorize(&self) -> SkewFactorization {
+        let n = self.data.nrows();
+        let mut a = self.data.clone();
+        let mut l = DMatrix::<f64>::identity(n, n);
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut perm_sign = 1.0;
+
+        for k in 0..n.saturating_sub(2) {
+            let mut kp = k + 1;
+            for r in (k + 2)..n {
+                if a[(r, k)].abs() > a[(kp, k)].abs() {
+                    kp = r;
+                }
+            }
+            if kp != k + 1 {
+                a.swap_rows(k + 1, kp);
+                a.swap_columns(k + 1, kp);
+                // Swap the parts of L that are already computed, as in LU.
+                for c in 0..=k {
+                    l.swap((k + 1, c), (kp, c));
+                }
+                perm.swap(k + 1, kp);
+                perm_sign = -perm_sign;
+            }
+
+            let pivot = a[(k + 1, k)];
+            if pivot == 0.0 {
+                // Nothing left to eliminate in this column.
+                continue;
+            }
+
+            let tau: Vec<f64> = ((k + 2)..n).map(|i| a[(i, k)] / pivot).collect();
+            let col: Vec<f64> = ((k + 2)..n).map(|i| a[(i, k + 1)]).collect();
+            for (ii, &tau_i) in tau.iter().enumerate() {
+                let i = k + 2 + ii;
+                l[(i, k + 1)] = tau_i;
+                for (jj, &tau_j) in tau.iter().enumerate() {
+                    a[(i, k + 2 + jj)] += tau_i * col[jj] - col[ii] * tau_j;
+                }
+                a[(i, k)] = 0.0;
+                a[(k, i)] = 0.0;
+            }
+        }
+
+        SkewFactorization {
+            perm,
+            perm_sign,
+            l,
+            t: a,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PivotStrategy;
+
+    #[test]
+    fn factorization_queries_match_their_standalone_counterparts() {
+        let values: Vec<f64> = (1..=28).map(|k| ((k * 3) % 11) as f64 - 5.0).collect();
+        let matrix = SkewMatrix::from_upper_triangle(8, &values).unwrap();
+        let a = matrix.as_matrix();
+        let f = matrix.factorize();
+
+        let pf = matrix.pfaffian_with_pivoting(PivotStrategy::Partial);
+        assert!((f.pfaffian() - pf).abs() < 1e-10 * pf.abs());
+        let (sign, log_abs) = matrix.log_pfaffian();
+        let (f_sign, f_log_abs) = f.log_pfaffian();
+        assert_eq!(f_sign, sign);
+        assert!((f_log_abs - log_abs).abs() < 1e-12);
+
+        let b = DVector::from_fn(8, |i, _| i as f64 - 3.5);
+        let x = f.solve(&b).unwrap();
+        let lu_x = a.clone().lu().solve(&b).unwrap();
+        assert!((&x - lu_x).amax() < 1e-10);
+        let inverse = f.inverse().unwrap();
+        assert!((&inverse - a.clone().try_inverse().unwrap()).amax() < 1e-10);
+        assert!((a * inverse - DMatrix::identity(8, 8)).amax() < 1e-10);
+
+        let singular = SkewMatrix::from_upper_triangle(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
+        let f = singular.factorize();
+        assert_eq!(f.pfaffian(), 0.0);
+        assert_eq!(f.log_pfaffian(), (0.0, f64::NEG_INFINITY));
+        assert!(f.solve(&DVector::zeros(4)).is_none());
+        assert!(f.inverse().is_none());
+    }
+}
@@ -0,0 +1,396 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::SkewMatrix;
+use crate::expansion::first_row_pairings;
+
+/// A partially built matching on the depth-first search stack.
+struct PartialMatching {
+    remaining: Vec<usize>,
+    pairs: Vec<(usize, usize)>,
+    contribution: f64,
+}
+
+/// A partial matching in the best-first search of `pfaffian_approx`, ordered by
+/// an upper bound on |contribution| over all of its completions.
+struct BoundedMatching {
+    bound: f64,
+    partial: PartialMatching,
+}
+
+impl PartialEq for BoundedMatching {
+    fn eq(&self, other: &Self) -> bool {
+        self.bound.total_cmp(&other.bound) == Ordering::Equal
+    }
+}
+
+impl Eq for BoundedMatching {}
+
+impl PartialOrd for BoundedMatching {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BoundedMatching {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound.total_cmp(&other.bound)
+    }
+}
+
+/// (r - 1)!!, the number of perfect matchings of a complete graph on r vertices.
+fn double_factorial_odd(r: usize) -> f64 {
+    (1..r).step_by(2).map(|k| k as f64).product()
+}
+
+/// Iterator over the perfect matchings of a SkewMatrix, see `SkewMatrix::matchings`.
+pub struct Matchings<'a> {
+    matrix: &'a SkewMatrix,
+    stack: Vec<PartialMatching>,
+}
+
+impl Iterator for Matchings<'_> {
+    type Item = (Vec<(usize, usize)>, f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(partial) = self.stack.pop() {
+            if partial.remaining.is_empty() {
+                return Some((partial.pairs, partial.contribution));
+            }
+
+            // Same step as the recursion: fix the first remaining vertex and
+            // pair it with each of the others. Push in reverse so that the
+            // matchings come out in lexicographic order.
+            let i = partial.remaining[0];
+            for (j, sign, remaining) in first_row_pairings(&partial.remaining).rev() {
+                let a_ij = self.matrix.data[(i, j)];
+                if a_ij == 0.0 {
+                    continue;
+                }
+
+                let mut pairs = partial.pairs.clone();
+                pairs.push((i, j));
+                self.stack.push(PartialMatching {
+                    remaining,
+                    pairs,
+                    contribution: partial.contribution * sign * a_ij,
+                });
+            }
+        }
+        None
+    }
+}
+
+impl SkewMatrix {
+    /// Iterates over every perfect matching of the matrix, viewed as a weighted
+    /// graph with an edge (i, j) wherever A_ij is nonzero.
+    ///
+    /// Each item is the list of matched pairs (i, j) with i < j, plus the
+    /// signed contribution sgn(π) * ∏ A_ij of that matching to the Pfaffian.
+    /// Summing all the contributions gives `pfaffian()`.
+    ///
+    /// A dense matrix has (n-1)!! matchings, so this gets slow quickly.
+    pub fn matchings(&self) -> Matchings<'_> {
+        Matchings {
+            matrix: self,
+            stack: vec![PartialMatching {
+                remaining: (0..self.data.nrows()).collect(),
+                pairs: Vec::new(),
+                contribution: 1.0,
+            }],
+        }
+    }
+
+    /// Computes the Pfaffian as a permanent-style sum over matchings: each
+    /// matching's unsigned weight ∏ A_ij, times (-1)^(number of crossings).
+    ///
+    /// Two pairs (a, b) and (c, d), with a < b and c < d, cross when
+    /// a < c < b < d, i.e. their chords cross when the vertices are drawn in
+    /// order around a circle. The crossing parity is the sign of the
+    /// matching's permutation, but it is computed from scratch here rather
+    /// than tracked through the recursion, so this is an independent check
+    /// on `pfaffian()`. Only the list of pairs from `matchings()` is used.
+    pub fn pfaffian_via_matching_permanent(&self) -> f64 {
+        let mut total = 0.0;
+        for (pairs, _) in self.matchings() {
+            let mut crossings = 0;
+            for (k, &(a, b)) in pairs.iter().enumerate() {
+                for &(c, d) in &pairs[k + 1..] {
+                    if (a < c && c < b && b < d) || (c < a && a < d && d < b) {
+                        crossings += 1;
+                    }
+                }
+            }
+            let weight: f64 = pairs.iter().map(|&(i, j)| self.data[(i, j)]).product();
+            total += if crossings % 2 == 1 { -weight } else { weight };
+        }
+        total
+    }
+
+    /// Counts the perfect matchings of the graph with an edge wherever A_ij
+    /// is nonzero, ignoring the weights.
+    ///
+    /// This walks every matching one by one, so it costs as much as
+    /// `matchings()`: up to (n-1)!! steps, which is 2,027,025 at n = 16.
+    pub fn perfect_matching_count(&self) -> u64 {
+        self.matchings().count() as u64
+    }
+
+    /// Returns the k matchings with the largest |contribution|, largest first.
+    /// Summing their contributions gives an approximation to the Pfaffian
+    /// from its dominant terms.
+    pub fn dominant_matchings(&self, k: usize) -> Vec<(Vec<(usize, usize)>, f64)> {
+        let mut all: Vec<_> = self.matchings().collect();
+        all.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        all.truncate(k);
+        all
+    }
+}
+
+/// Summary statistics of the signed matching contributions, from
+/// `SkewMatrix::matching_statistics`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchingStats {
+    /// How many matchings have a nonzero contribution.
+    pub count: usize,
+    /// The mean contribution, so count * mean is the Pfaffian.
+    pub mean: f64,
+    /// The population variance of the contributions.
+    pub variance: f64,
+    pub min: f64,
+    pub max: f64,
+    /// The fraction of contributions that are positive.
+    pub positive_fraction: f64,
+    /// The fraction of contributions that are negative.
+    pub negative_fraction: f64,
+}
+
+impl SkewMatrix {
+    /// Statistics over the signed contributions of every perfect matching
+    /// (those with all edges nonzero, as yielded by `matchings`).
+    ///
+    /// When the positive and negative fractions are close and the variance
+    /// is large next to mean², the Pfaffian is a small difference of large
+    /// terms, which is the sign problem in Monte Carlo language. With no
+    /// matchings at all, count is 0 and the other fields are NaN.
+    pub fn matching_statistics(&self) -> MatchingStats {
+        // Welford's update, so the variance doesn't lose precision.
+        let mut count = 0usize;
+        let mut mean = 0.0;
+        let mut m2 = 0.0;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        let mut positive = 0usize;
+        let mut negative = 0usize;
+
+        for (_, contribution) in self.matchings() {
+            count += 1;
+            let delta = contribution - mean;
+            mean += delta / count as f64;
+            m2 += delta * (contribution - mean);
+            min = min.min(contribution);
+            max = max.max(contribution);
+            if contribution > 0.0 {
+                positive += 1;
+            } else if contribution < 0.0 {
+                negative += 1;
+            }
+        }
+
+        if count == 0 {
+            return MatchingStats {
+                count,
+                mean: f64::NAN,
+                variance: f64::NAN,
+                min: f64::NAN,
+                max: f64::NAN,
+                positive_fraction: f64::NAN,
+                negative_fraction: f64::NAN,
+            };
+        }
+        MatchingStats {
+            count,
+            mean,
+            variance: m2 / count as f64,
+            min,
+            max,
+            positive_fraction: positive as f64 / count as f64,
+            negative_fraction: negative as f64 / count as f64,
+        }
+    }
+}
+
+impl SkewMatrix {
+    /// An upper bound on |∏ A_uw| over every perfect matching of `remaining`.
+    /// Each vertex contributes the square root of its largest edge weight,
+    /// since every matched edge (u, w) is shared by exactly two vertices.
+    fn completion_bound(&self, remaining: &[usize]) -> f64 {
+        remaining
+            .iter()
+            .map(|&u| {
+                remaining
+                    .iter()
+                    .map(|&w| self.data[(u, w)].abs())
+                    .fold(0.0, f64::max)
+                    .sqrt()
+            })
+            .product()
+    }
+
+    /// Approximates the Pfaffian by summing only its `max_terms` largest
+    /// matchings. See `pfaffian_approx_with_bound`.
+    pub fn pfaffian_approx(&self, max_terms: usize) -> f64 {
+        self.pfaffian_approx_with_bound(max_terms).0
+    }
+
+    /// Approximates the Pfaffian by summing only its `max_terms` largest
+    /// matchings, returning (approximation, bound on |Pf - approximation|).
+    ///
+    /// This is a best-first branch-and-bound search: partial matchings are
+    /// expanded in order of an upper bound on what any completion could
+    /// contribute, so complete matchings come out in decreasing |contribution|
+    /// without enumerating the rest. Whatever is still on the frontier when we
+    /// stop gives the remainder bound. As `max_terms` reaches the number of
+    /// matchings the result is exact and the bound goes to zero.
+    pub fn pfaffian_approx_with_bound(&self, max_terms: usize) -> (f64, f64) {
+        let remaining: Vec<usize> = (0..self.data.nrows()).collect();
+        let mut frontier = BinaryHeap::new();
+        frontier.push(BoundedMatching {
+            bound: self.completion_bound(&remaining),
+            partial: PartialMatching {
+                remaining,
+                pairs: Vec::new(),
+                contribution: 1.0,
+            },
+        });
+
+        let mut total = 0.0;
+        let mut terms = 0;
+        while terms < max_terms {
+            let Some(BoundedMatching { partial, .. }) = frontier.pop() else {
+                break;
+            };
+            if partial.remaining.is_empty() {
+                total += partial.contribution;
+                terms += 1;
+                continue;
+            }
+
+            let i = partial.remaining[0];
+            for (j, sign, remaining) in first_row_pairings(&partial.remaining) {
+                let a_ij = self.data[(i, j)];
+                if a_ij == 0.0 {
+                    continue;
+                }
+
+                let contribution = partial.contribution * sign * a_ij;
+                frontier.push(BoundedMatching {
+                    bound: contribution.abs() * self.completion_bound(&remaining),
+                    partial: PartialMatching {
+                        remaining,
+                        pairs: Vec::new(),
+                        contribution,
+                    },
+                });
+            }
+        }
+
+        let remainder_bound = frontier
+            .iter()
+            .map(|b| b.bound * double_factorial_odd(b.partial.remaining.len()))
+            .fold(0.0, |acc, term| acc + term);
+        (total, remainder_bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dense 6x6 matrix with 15 distinct matchings, all nonzero.
+    fn example_6x6() -> SkewMatrix {
+        let values: Vec<f64> = (1..=15).map(|k| k as f64).collect();
+        SkewMatrix::from_upper_triangle(6, &values).unwrap()
+    }
+
+    #[test]
+    fn dominant_matchings_are_ranked_and_sum_to_the_pfaffian() {
+        let matrix = example_6x6();
+        let top = matrix.dominant_matchings(20);
+        assert_eq!(top.len(), 15);
+        assert!(top.windows(2).all(|w| w[0].1.abs() >= w[1].1.abs()));
+
+        let total: f64 = top.iter().map(|(_, c)| c).sum();
+        assert!((total - matrix.pfaffian()).abs() < 1e-9);
+
+        let top3 = matrix.dominant_matchings(3);
+        assert_eq!(top3, top[..3].to_vec());
+    }
+
+    #[test]
+    fn approximation_converges_to_the_pfaffian_within_its_bound() {
+        let matrix = example_6x6();
+        let exact = matrix.pfaffian();
+        for max_terms in 0..=15 {
+            let (approx, bound) = matrix.pfaffian_approx_with_bound(max_terms);
+            assert!(
+                (exact - approx).abs() <= bound + 1e-9,
+                "{} terms",
+                max_terms
+            );
+        }
+        let (approx, bound) = matrix.pfaffian_approx_with_bound(15);
+        assert!((approx - exact).abs() < 1e-9);
+        assert_eq!(bound, 0.0);
+        assert_eq!(matrix.pfaffian_approx(100), approx);
+    }
+
+    #[test]
+    fn statistics_of_the_4x4_example_match_its_three_terms() {
+        // The contributions are a·f = 6, -b·e = -10 and c·d = 12.
+        let matrix = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let stats = matrix.matching_statistics();
+        assert_eq!(stats.count, 3);
+        assert!((stats.mean - 8.0 / 3.0).abs() < 1e-12);
+        // ((10² + 38² + 28²) / 9) / 3
+        assert!((stats.variance - 2328.0 / 27.0).abs() < 1e-12);
+        assert_eq!((stats.min, stats.max), (-10.0, 12.0));
+        assert!((stats.positive_fraction - 2.0 / 3.0).abs() < 1e-12);
+        assert!((stats.negative_fraction - 1.0 / 3.0).abs() < 1e-12);
+
+        let empty = SkewMatrix::from_upper_triangle(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
+        let stats = empty.matching_statistics();
+        assert_eq!(stats.count, 0);
+        assert!(stats.mean.is_nan());
+    }
+
+    #[test]
+    fn complete_graphs_have_double_factorial_matchings() {
+        let k4 = SkewMatrix::from_upper_triangle(4, &[1.0, -2.0, 0.5, 3.0, -1.0, 7.0]).unwrap();
+        assert_eq!(k4.perfect_matching_count(), 3);
+        assert_eq!(example_6x6().perfect_matching_count(), 15);
+
+        // A 4-cycle 0-1-2-3-0 has only the two matchings {01, 23}, {03, 12}.
+        let cycle = SkewMatrix::from_upper_triangle(4, &[1.0, 0.0, 1.0, 1.0, 0.0, 1.0]).unwrap();
+        assert_eq!(cycle.perfect_matching_count(), 2);
+    }
+
+    #[test]
+    fn matching_permanent_agrees_with_the_recursion_up_to_n_8() {
+        for n in (0..=8usize).step_by(2) {
+            let values: Vec<f64> = (0..n * n.saturating_sub(1) / 2)
+                .map(|k| ((k * 7 + 3) % 13) as f64 / 4.0 - 1.5)
+                .collect();
+            let matrix = SkewMatrix::from_upper_triangle(n, &values).unwrap();
+            let expected = matrix.pfaffian();
+            let pf = matrix.pfaffian_via_matching_permanent();
+            assert!(
+                (pf - expected).abs() < 1e-9,
+                "n = {}: {} vs {}",
+                n,
+                pf,
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use nalgebra::DVector;
+
+use crate::SkewMatrix;
+use crate::elimination::{argmax_in_column, swap_symmetric};
+
+impl SkewMatrix {
+    /// Computes the Pfaffian with the working matrix stored in f32 but all
+    /// arithmetic and accumulation done in f64.
+    ///
+    /// This is partial-pivoting elimination where the n x n trailing matrix,
+    /// the only O(n^2) storage, is an f32 copy, so it takes half the memory
+    /// of `pfaffian_with_pivoting`. Each rank-2 update reads its entries,
+    /// computes in f64 and rounds once on the way back, and the pivots go
+    /// into an f64 sign and log-magnitude. Entries therefore carry about 7
+    /// significant digits, and the result is only good to roughly
+    /// 1e-7 times the growth of the elimination, much like doing it all in
+    /// f32. What it avoids is the f32 trouble with the result itself: a
+    /// product of n/2 pivots leaves f32's range (about 1e±38) quickly and
+    /// picks up rounding at every multiply, while here it gets the range
+    /// and precision of f64. Entries must still fit in f32.
+    pub fn pfaffian_mixed_storage(&self) -> f64 {
+        let n = self.data.nrows();
+        let mut a = self.data.map(|x| x as f32);
+        let mut sign = 1.0;
+        let mut log_abs = 0.0;
+
+        for k in (0..n).step_by(2) {
+            let kp = argmax_in_column(&a, k, k + 1..n);
+            if kp != k + 1 {
+                swap_symmetric(&mut a, k + 1, kp);
+                sign = -sign;
+            }
+
+            let pivot = a[(k, k + 1)] as f64;
+            if pivot == 0.0 {
+                return 0.0;
+            }
+            sign *= pivot.signum();
+            log_abs += pivot.abs().ln();
+
+            // The same update as `pfaffian_with_pivoting`,
+            // A[r][c] += tau_r col_c - col_r tau_c, with tau = -A[k+2.., k] / pivot
+            // and col = A[k+2.., k+1], but formed in f64.
+            let tau = DVector::from_fn(n - k - 2, |r, _| -(a[(k + 2 + r, k)] as f64) / pivot);
+            let col = DVector::from_fn(n - k - 2, |r, _| a[(k + 2 + r, k + 1)] as f64);
+            for c in 0..tau.len() {
+                for r in 0..tau.len() {
+                    let entry = &mut a[(k + 2 + r, k + 2 + c)];
+                    *entry = (*entry as f64 + tau[r] * col[c] - col[r] * tau[c]) as f32;
+                }
+            }
+        }
+        sign * log_abs.exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PivotStrategy;
+    use crate::elimination::eliminate_in_place;
+
+    #[test]
+    fn mixed_storage_keeps_the_range_that_f32_alone_loses() {
+        // Entries up to 1000 in magnitude: each fits f32 easily, but the
+        // product of the 20 pivots is far beyond f32's 3.4e38.
+        let n = 40;
+        let values: Vec<f64> = (0..n * (n - 1) / 2)
+            .map(|k| ((k * 13 + 5) as f64).sin() * 1000.0)
+            .collect();
+        let matrix = SkewMatrix::from_upper_triangle(n, &values).unwrap();
+        let reference = matrix.pfaffian_with_pivoting(PivotStrategy::Partial);
+        assert!(reference.abs() > 1e40);
+
+        let mut a = matrix.as_matrix().map(|x| x as f32);
+        let mut pivots = Vec::new();
+        let sign = eliminate_in_place(&mut a, PivotStrategy::Partial, &mut pivots);
+        let f32_only = sign as f32 * pivots.iter().product::<f32>();
+        assert!(f32_only.is_infinite());
+
+        let mixed = matrix.pfaffian_mixed_storage();
+        assert!(
+            (mixed - reference).abs() < 1e-4 * reference.abs(),
+            "{} vs {}",
+            mixed,
+            reference
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use nalgebra::{DMatrix, DVector};
+
+use crate::{PivotStrategy, SkewMatrix};
+
+/// Computes the overlap Pfaffian of two fermionic Gaussian states, given
+/// their 2m x 2m Majorana correlation matrices M1 and M2 (m modes each).
+///
+/// We assemble the 4m x 4m skew matrix
+///
+///    M1  -I
+///     I  -M2
+///
+/// and return its Pfaffian. By the Schur complement this is
+/// Pf(M1) · Pf(M1⁻¹ - M2). For pure states M² = -I, so M1⁻¹ = -M1 and
+/// Pf(M1) = ±1, which gives ±Pf(M1 + M2) and the overlap
+/// |⟨ψ1|ψ2⟩|² = 2^(-m) |result|. The sign of the result is only meaningful
+/// once the overall phase convention of the states has been fixed.
+///
+/// Panics if M1 and M2 have different sizes.
+pub fn fermionic_overlap(m1: &SkewMatrix, m2: &SkewMatrix) -> f64 {
+    let n = m1.data.nrows();
+    assert_eq!(
+        n,
+        m2.data.nrows(),
+        "Correlation matrices must have the same dimensions."
+    );
+
+    let mut combined = DMatrix::<f64>::zeros(2 * n, 2 * n);
+    combined.view_mut((0, 0), (n, n)).copy_from(&m1.data);
+    combined.view_mut((n, n), (n, n)).copy_from(&(-&m2.data));
+    for k in 0..n {
+        combined[(k, n + k)] = -1.0;
+        combined[(n + k, k)] = 1.0;
+    }
+
+    SkewMatrix::from_skew_unchecked(combined).pfaffian_with_pivoting(PivotStrategy::Partial)
+}
+
+/// Assembles the block skew matrix
+///
+///    A   C
+///   -Cᵀ  B
+///
+/// from a p x p skew A, a q x q skew B and a p x q coupling C, and returns
+/// its Pfaffian. This is the usual "doubled" layout for overlaps of two
+/// pairing or Slater states.
+///
+/// Rows and columns 0..p belong to A and p..p+q to B, and the Pfaffian is
+/// taken in that order. Listing B first instead is a permutation of sign
+/// (-1)^(pq) = +1, since p and q are even, so
+/// `overlap_pfaffian(b, a, &-Cᵀ)` gives the same value. When A is
+/// invertible the Schur complement gives Pf = Pf(A) · Pf(B + Cᵀ A⁻¹ C).
+/// `fermionic_overlap` is the special case C = -I, B = -M2.
+///
+/// Panics if C isn't p x q.
+pub fn overlap_pfaffian(a: &SkewMatrix, b: &SkewMatrix, coupling: &DMatrix<f64>) -> f64 {
+    let (p, q) = (a.data.nrows(), b.data.nrows());
+    assert_eq!(
+        coupling.shape(),
+        (p, q),
+        "The coupling must have one row per row of A and one column per row of B."
+    );
+
+    let mut combined = DMatrix::<f64>::zeros(p + q, p + q);
+    combined.view_mut((0, 0), (p, p)).copy_from(&a.data);
+    combined.view_mut((p, p), (q, q)).copy_from(&b.data);
+    combined.view_mut((0, p), (p, q)).copy_from(coupling);
+    combined
+        .view_mut((p, 0), (q, p))
+        .copy_from(&(-coupling.transpose()));
+
+    SkewMatrix::from_skew_unchecked(combined).pfaffian_with_pivoting(PivotStrategy::Partial)
+}
+
+/// The Pfaffian of the Gram matrix G_ij = v_iᵀ J v_j of a set of vectors
+/// under the skew bilinear form J.
+///
+/// G is skew because J is. With V the d x k matrix whose columns are the
+/// vectors, G = Vᵀ J V, so for k = d this is det(V) Pf(J): the symplectic
+/// volume of the parallelepiped the vectors span, with its orientation.
+/// For k < d it is the volume of their span as measured by J restricted to
+/// it, and zero once the vectors are linearly dependent or span a subspace
+/// the form is degenerate on. An odd number of vectors gives 0, and none at
+/// all gives 1. We keep only the exact antisymmetric part of the computed
+/// G, as rounding can leave VᵀJV slightly asymmetric.
+/// Panics if a vector's length doesn't match the size of J.
+pub fn gram_pfaffian(vectors: &[DVector<f64>], form: &SkewMatrix) -> f64 {
+    let d = form.data.nrows();
+    if let Some(v) = vectors.iter().find(|v| v.len() != d) {
+        panic!(
+            "Every vector needs {} entries to match the form, got {}.",
+            d,
+            v.len()
+        );
+    }
+    let k = vectors.len();
+    if !k.is_multiple_of(2) {
+        return 0.0;
+    }
+
+    let v = DMatrix::from_fn(d, k, |row, col| vectors[col][row]);
+    let gram = v.transpose() * &form.data * &v;
+    SkewMatrix::from_skew_unchecked((&gram - gram.transpose()) * 0.5)
+        .pfaffian_with_pivoting(PivotStrategy::Partial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The two-mode vacuum, and the state reached from it by rotating
+    /// Majoranas 0 and 2 by θ. Their overlap is |⟨ψ1|ψ2⟩|² = cos²(θ/2).
+    fn vacuum_and_rotated(theta: f64) -> (SkewMatrix, SkewMatrix) {
+        let vacuum = SkewMatrix::from_upper_triangle(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 1.0]).unwrap();
+        let mut r = DMatrix::<f64>::identity(4, 4);
+        let (s, c) = theta.sin_cos();
+        r[(0, 0)] = c;
+        r[(2, 2)] = c;
+        r[(0, 2)] = -s;
+        r[(2, 0)] = s;
+        let rotated = SkewMatrix::from_skew_unchecked(&r * &vacuum.data * r.transpose());
+        (vacuum, rotated)
+    }
+
+    #[test]
+    fn overlap_of_rotated_two_mode_states_is_cos_squared_half_angle() {
+        for theta in [0.0, 0.4, 1.3, 2.0, std::f64::consts::PI] {
+            let (m1, m2) = vacuum_and_rotated(theta);
+            let overlap = fermionic_overlap(&m1, &m2).abs() / 4.0;
+            let expected = (theta / 2.0).cos().powi(2);
+            assert!((overlap - expected).abs() < 1e-12, "θ = {}", theta);
+        }
+    }
+
+    #[test]
+    fn overlap_pfaffian_matches_the_hand_assembled_matrix() {
+        let a = SkewMatrix::from_upper_triangle(2, &[2.0]).unwrap();
+        let b = SkewMatrix::from_upper_triangle(2, &[-3.0]).unwrap();
+        let c = DMatrix::from_row_slice(2, 2, &[1.0, 4.0, 5.0, 0.5]);
+        // Upper triangle (a01, a02, a03, a12, a13, a23) = (A, C00, C01, C10, C11, B),
+        // so Pf = A B - C00 C11 + C01 C10 = -6 - 0.5 + 20.
+        let by_hand = SkewMatrix::from_upper_triangle(4, &[2.0, 1.0, 4.0, 5.0, 0.5, -3.0]).unwrap();
+        assert_eq!(by_hand.pfaffian(), 13.5);
+        assert!((overlap_pfaffian(&a, &b, &c) - 13.5).abs() < 1e-12);
+        assert!((overlap_pfaffian(&b, &a, &-c.transpose()) - 13.5).abs() < 1e-12);
+
+        // Pf(A) Pf(B + Cᵀ A⁻¹ C) for the Schur complement.
+        let a_inverse = a.as_matrix().clone().try_inverse().unwrap();
+        let schur = b.as_matrix() + c.transpose() * a_inverse * &c;
+        assert!((2.0 * schur[(0, 1)] - 13.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn gram_pfaffian_under_the_standard_symplectic_form() {
+        // ω(u, w) = u0 w1 - u1 w0 + u2 w3 - u3 w2.
+        let form = SkewMatrix::from_upper_triangle(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 1.0]).unwrap();
+        let vectors: Vec<DVector<f64>> = [
+            [1.0, 2.0, 0.0, 0.0],
+            [0.0, 1.0, 1.0, 0.0],
+            [3.0, 0.0, 0.0, 1.0],
+            [0.0, 0.0, 2.0, 1.0],
+        ]
+        .iter()
+        .map(|v| DVector::from_row_slice(v))
+        .collect();
+        // G01 = 1, G02 = -6, G03 = 0, G12 = -2, G13 = 1, G23 = -2, so
+        // Pf = G01 G23 - G02 G13 + G03 G12 = -2 + 6 + 0, which is det(V).
+        assert!((gram_pfaffian(&vectors, &form) - 4.0).abs() < 1e-12);
+        assert!((gram_pfaffian(&vectors[..2], &form) - 1.0).abs() < 1e-12);
+        assert_eq!(gram_pfaffian(&vectors[..3], &form), 0.0);
+        assert_eq!(gram_pfaffian(&[], &form), 1.0);
+
+        let dependent = [vectors[0].clone(), &vectors[0] * 2.0];
+        assert_eq!(gram_pfaffian(&dependent, &form), 0.0);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::PfaffianError;
+
+/// A skew-symmetric matrix that only stores its strict upper triangle.
+///
+/// The lower triangle is just the negation of the upper one and the diagonal
+/// is zero, so we keep the n(n-1)/2 upper values in one contiguous Vec, in the
+/// same row-by-row order as `SkewMatrix::from_upper_triangle`. That is a bit
+/// under half the memory of a dense n x n DMatrix, which adds up for large n.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedSkewMatrix {
+    n: usize,
+    values: Vec<f64>,
+}
+
+impl PackedSkewMatrix {
+    /// Creates a new PackedSkewMatrix from a list of upper-triangular values,
+    /// with the same validation as `SkewMatrix::from_upper_triangle`.
+    pub fn from_upper_triangle(n: usize, values: &[f64]) -> Result<Self, PfaffianError> {
+        if !n.is_multiple_of(2) {
+            return Err(PfaffianError::OddDimension { n });
+        }
+        let expected_vals = n * n.saturating_sub(1) / 2;
+        if values.len() != expected_vals {
+            return Err(PfaffianError::WrongValueCount {
+                n,
+                expected: expected_vals,
+                got: values.len(),
+            });
+        }
+        if let Some(index) = values.iter().position(|v| !v.is_finite()) {
+            return Err(PfaffianError::NonFinite { index });
+        }
+        Ok(Self {
+            n,
+            values: values.to_vec(),
+        })
+    }
+
+    /// The dimension n of the n x n matrix.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Where A[i][j] (with i < j) lives in the packed Vec.
+    fn index(&self, i: usize, j: usize) -> usize {
+        i * self.n - i * (i + 1) / 2 + (j - i - 1)
+    }
+
+    /// Reads A[i][j], filling in the implied lower triangle and diagonal.
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        match i.cmp(&j) {
+            std::cmp::Ordering::Less => self.values[self.index(i, j)],
+            std::cmp::Ordering::Greater => -self.values[self.index(j, i)],
+            std::cmp::Ordering::Equal => 0.0,
+        }
+    }
+
+    /// Writes A[i][j] (and implicitly A[j][i] = -value). Requires i != j.
+    fn set(&mut self, i: usize, j: usize, value: f64) {
+        if i < j {
+            let idx = self.index(i, j);
+            self.values[idx] = value;
+        } else {
+            let idx = self.index(j, i);
+            self.values[idx] = -value;
+        }
+    }
+
+    /// Swaps rows/cols p and q (p != q) in packed form.
+    fn swap_symmetric(&mut self, p: usize, q: usize) {
+        for r in 0..self.n {
+            if r != p && r != q {
+                let a_pr = self.get(p, r);
+                let a_qr = self.get(q, r);
+                self.set(p, r, a_qr);
+                self.set(q, r, a_pr);
+            }
+        }
+        // The (p, q) entry itself moves to (q, p), which flips its sign.
+        let a_pq = self.get(p, q);
+        self.set(p, q, -a_pq);
+    }
+
+    /// Computes the Pfaffian by partial-pivoting skew elimination, working
+    /// directly on the packed upper triangle. Gives the same result as
+    /// `SkewMatrix::pfaffian_with_pivoting(PivotStrategy::Partial)`.
+    pub fn pfaffian(&self) -> f64 {
+        let n = self.n;
+        let mut a = self.clone();
+        let mut pf = 1.0;
+
+        for k in (0..n).step_by(2) {
+            let mut kp = k + 1;
+            for r in (k + 2)..n {
+                if a.get(r, k).abs() > a.get(kp, k).abs() {
+                    kp = r;
+                }
+            }
+            if kp != k + 1 {
+                a.swap_symmetric(k + 1, kp);
+                pf = -pf;
+            }
+
+            let pivot = a.get(k, k + 1);
+            if pivot == 0.0 {
+                return 0.0;
+            }
+            pf *= pivot;
+
+            // Same update as the dense elimination, restricted to the upper
+            // triangle: A[i][j] += tau_i * A[j][k+1] - A[i][k+1] * tau_j.
+            let tau: Vec<f64> = ((k + 2)..n).map(|i| a.get(k, i) / pivot).collect();
+            let col: Vec<f64> = ((k + 2)..n).map(|i| a.get(i, k + 1)).collect();
+            let m = tau.len();
+            for ii in 0..m {
+                for jj in (ii + 1)..m {
+                    let idx = a.index(k + 2 + ii, k + 2 + jj);
+                    a.values[idx] += tau[ii] * col[jj] - col[ii] * tau[jj];
+                }
+            }
+        }
+        pf
+    }
+}
+
+/// Computes the Pfaffian straight from a packed upper triangle, in the same
+/// row-by-row order as `SkewMatrix::from_upper_triangle`, with no struct and
+/// no allocation. n is inferred from the length, n(n-1)/2.
+///
+/// n = 2, 4 and 6 are written out in closed form, which is the fastest path
+/// for tiny matrices in hot loops. Larger n falls back to the matching
+/// recursion over a bitmask of the remaining rows, which is (n-1)!! terms,
+/// so past n = 8 or so one of the O(n^3) methods will win.
+///
+/// Panics if the length isn't n(n-1)/2 for an even n, or if n > 64.
+pub fn pfaffian_upper(values: &[f64]) -> f64 {
+    let mut n: usize = 0;
+    while n * n.saturating_sub(1) / 2 < values.len() {
+        n += 1;
+    }
+    assert!(
+        n * n.saturating_sub(1) / 2 == values.len() && n.is_multiple_of(2),
+        "{} values is not the upper triangle of an even-sized matrix.",
+        values.len()
+    );
+    assert!(n <= 64, "pfaffian_upper only supports n <= 64.");
+
+    match n {
+        0 => 1.0,
+        2 => values[0],
+        4 => pf4(
+            values[0], values[1], values[2], values[3], values[4], values[5],
+        ),
+        6 => {
+            // Expand along row 0; a(i, j) is the (i, j) entry, i < j.
+            let a = |i: usize, j: usize| values[i * 6 - i * (i + 1) / 2 + (j - i - 1)];
+            let minor = |p, q, r, s| pf4(a(p, q), a(p, r), a(p, s), a(q, r), a(q, s), a(r, s));
+            a(0, 1) * minor(2, 3, 4, 5) - a(0, 2) * minor(1, 3, 4, 5) + a(0, 3) * minor(1, 2, 4, 5)
+                - a(0, 4) * minor(1, 2, 3, 5)
+                + a(0, 5) * minor(1, 2, 3, 4)
+        }
+        _ => pfaffian_upper_masked(values, n, u64::MAX >> (64 - n)),
+    }
+}
+
+/// The 4x4 Pfaffian af - be + cd of the upper triangle (a, b, c, d, e, f).
+fn pf4(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> f64 {
+    a * f - b * e + c * d
+}
+
+/// The matching recursion on the rows/cols whose bits are set in `mask`.
+fn pfaffian_upper_masked(values: &[f64], n: usize, mask: u64) -> f64 {
+    if mask == 0 {
+        return 1.0;
+    }
+    let i = mask.trailing_zeros() as usize;
+    let rest = mask & (mask - 1);
+
+    let mut total_sum = 0.0;
+    let mut sign = 1.0;
+    let mut others = rest;
+    while others != 0 {
+        let j = others.trailing_zeros() as usize;
+        others &= others - 1;
+        let a_ij = values[i * n - i * (i + 1) / 2 + (j - i - 1)];
+        total_sum += sign * a_ij * pfaffian_upper_masked(values, n, rest & !(1 << j));
+        sign = -sign;
+    }
+    total_sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PivotStrategy, SkewMatrix};
+
+    fn triangle(n: usize) -> Vec<f64> {
+        (0..n * n.saturating_sub(1) / 2)
+            .map(|k| ((k * 7 + 3) % 13) as f64 - 6.0)
+            .collect()
+    }
+
+    #[test]
+    fn packed_matches_dense_elimination() {
+        for n in [2, 4, 6, 10] {
+            let values = triangle(n);
+            let packed = PackedSkewMatrix::from_upper_triangle(n, &values).unwrap();
+            let dense = SkewMatrix::from_upper_triangle(n, &values).unwrap();
+            assert_eq!(packed.n(), n);
+            assert_eq!(packed.get(0, 1), -packed.get(1, 0));
+            let expected = dense.pfaffian_with_pivoting(PivotStrategy::Partial);
+            assert!(
+                (packed.pfaffian() - expected).abs() < 1e-12 * expected.abs(),
+                "n = {}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn pfaffian_upper_matches_the_recursion_up_to_n_8() {
+        for n in (0..=8).step_by(2) {
+            let values = triangle(n);
+            let expected = SkewMatrix::from_upper_triangle(n, &values)
+                .unwrap()
+                .pfaffian();
+            assert_eq!(pfaffian_upper(&values), expected, "n = {}", n);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_traits::{One, Zero};
+
+/// A p-adic integer known to K digits, i.e. an element of Z/p^K Z, stored
+/// as its residue in 0..p^K.
+///
+/// This is a `Ring`, so `SkewMatrix<PAdic<P, K>>::pfaffian()` runs the
+/// division-free recursion over the p-adics. That recursion only adds,
+/// subtracts and multiplies, and all three are exact modulo p^K, so the
+/// result is Pf(A) mod p^K with no precision lost along the way: all K
+/// digits are correct, however large the valuations of the intermediate
+/// terms. (Elimination would have to divide by pivots, and every division
+/// by p costs a digit, which is why it isn't offered here.)
+///
+/// P must be prime for `valuation` to mean anything, and p^K must fit in a
+/// u64; a larger power is a compile error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PAdic<const P: u64, const K: u32> {
+    residue: u64,
+}
+
+impl<const P: u64, const K: u32> PAdic<P, K> {
+    /// p^K, the modulus everything is reduced by.
+    pub const MODULUS: u64 = P.pow(K);
+
+    /// The p-adic integer congruent to `value` mod p^K.
+    pub fn new(value: i64) -> Self {
+        let m = Self::MODULUS as i128;
+        Self {
+            residue: (value as i128).rem_euclid(m) as u64,
+        }
+    }
+
+    /// The residue mod p^K, in 0..p^K.
+    pub fn residue(&self) -> u64 {
+        self.residue
+    }
+
+    /// The p-adic valuation: how many times p divides the value. Returns
+    /// None for zero, whose valuation is at least K but otherwise unknown at
+    /// this precision.
+    pub fn valuation(&self) -> Option<u32> {
+        if self.residue == 0 {
+            return None;
+        }
+        let mut v = 0;
+        let mut r = self.residue;
+        while r.is_multiple_of(P) {
+            r /= P;
+            v += 1;
+        }
+        Some(v)
+    }
+
+    fn from_residue(residue: u128) -> Self {
+        Self {
+            residue: (residue % Self::MODULUS as u128) as u64,
+        }
+    }
+}
+
+impl<const P: u64, const K: u32> Add for PAdic<P, K> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_residue(self.residue as u128 + rhs.residue as u128)
+    }
+}
+
+impl<const P: u64, const K: u32> Sub for PAdic<P, K> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const P: u64, const K: u32> Neg for PAdic<P, K> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_residue(Self::MODULUS as u128 - self.residue as u128)
+    }
+}
+
+impl<const P: u64, const K: u32> Mul for PAdic<P, K> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::from_residue(self.residue as u128 * rhs.residue as u128)
+    }
+}
+
+impl<const P: u64, const K: u32> Zero for PAdic<P, K> {
+    fn zero() -> Self {
+        Self::new(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.residue == 0
+    }
+}
+
+impl<const P: u64, const K: u32> One for PAdic<P, K> {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkewMatrix;
+
+    fn reduced<const P: u64, const K: u32>(values: &[i64]) -> PAdic<P, K> {
+        let values: Vec<PAdic<P, K>> = values.iter().map(|&x| PAdic::new(x)).collect();
+        SkewMatrix::from_upper_triangle_generic(6, &values)
+            .unwrap()
+            .pfaffian()
+    }
+
+    #[test]
+    fn padic_pfaffian_is_the_integer_pfaffian_mod_p_to_the_k() {
+        let values: Vec<i64> = (1..=15).map(|k| (k * k * 37) % 101 - 50).collect();
+        let exact = SkewMatrix::from_upper_triangle_generic(6, &values)
+            .unwrap()
+            .pfaffian();
+        assert_ne!(exact, 0);
+
+        let two_adic = reduced::<2, 10>(&values);
+        assert_eq!(two_adic, PAdic::new(exact));
+        assert_eq!(two_adic.residue(), exact.rem_euclid(1 << 10) as u64);
+        assert_eq!(
+            two_adic.valuation(),
+            Some(exact.trailing_zeros()).filter(|&v| v < 10)
+        );
+        assert_eq!(reduced::<3, 5>(&values), PAdic::new(exact));
+        assert_eq!(reduced::<101, 2>(&values), PAdic::new(exact));
+    }
+}
@@ -0,0 +1,137 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_traits::{One, Zero};
+
+/// A univariate polynomial with f64 coefficients, stored lowest degree first.
+///
+/// This is a `Ring`, so a `SkewMatrix<Polynomial>` has a `pfaffian()`,
+/// computed symbolically by the division-free recursion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    coeffs: Vec<f64>,
+}
+
+impl Polynomial {
+    /// Builds c_0 + c_1 x + c_2 x^2 + ... from its coefficients.
+    /// Trailing zeros are dropped, so equal polynomials compare equal.
+    pub fn new(coeffs: Vec<f64>) -> Self {
+        let mut p = Self { coeffs };
+        p.trim();
+        p
+    }
+
+    /// The constant polynomial c.
+    pub fn constant(c: f64) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// The polynomial x.
+    pub fn x() -> Self {
+        Self::new(vec![0.0, 1.0])
+    }
+
+    /// The coefficients, lowest degree first. Empty for the zero polynomial.
+    pub fn coeffs(&self) -> &[f64] {
+        &self.coeffs
+    }
+
+    /// The degree, or None for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    /// Evaluates the polynomial at x by Horner's rule.
+    pub fn eval(&self, x: f64) -> f64 {
+        self.coeffs.iter().rev().fold(0.0, |acc, &c| acc * x + c)
+    }
+
+    fn trim(&mut self) {
+        while self.coeffs.last() == Some(&0.0) {
+            self.coeffs.pop();
+        }
+    }
+}
+
+impl Add for Polynomial {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (mut long, short) = if self.coeffs.len() >= rhs.coeffs.len() {
+            (self.coeffs, rhs.coeffs)
+        } else {
+            (rhs.coeffs, self.coeffs)
+        };
+        for (a, b) in long.iter_mut().zip(short) {
+            *a += b;
+        }
+        Self::new(long)
+    }
+}
+
+impl Neg for Polynomial {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(self.coeffs.into_iter().map(|c| -c).collect())
+    }
+}
+
+impl Sub for Polynomial {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl Mul for Polynomial {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.coeffs.is_empty() || rhs.coeffs.is_empty() {
+            return Self::zero();
+        }
+        let mut out = vec![0.0; self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                out[i + j] += a * b;
+            }
+        }
+        Self::new(out)
+    }
+}
+
+impl Zero for Polynomial {
+    fn zero() -> Self {
+        Self { coeffs: Vec::new() }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+}
+
+impl One for Polynomial {
+    fn one() -> Self {
+        Self::constant(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkewMatrix;
+
+    #[test]
+    fn pfaffian_of_polynomial_entries_matches_hand_expansion() {
+        let x = Polynomial::x;
+        let c = Polynomial::constant;
+        // Pf = a·f - b·e + c·d = x·x² - 1·x + (x + 1)·2 = x³ + x + 2.
+        let values = [x(), c(1.0), x() + c(1.0), c(2.0), x(), x() * x()];
+        let matrix = SkewMatrix::from_upper_triangle_generic(4, &values).unwrap();
+        let pf = matrix.pfaffian();
+        assert_eq!(pf, Polynomial::new(vec![2.0, 1.0, 0.0, 1.0]));
+        assert_eq!(pf.degree(), Some(3));
+        assert_eq!(pf.eval(2.0), 12.0);
+    }
+}
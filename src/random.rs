@@ -0,0 +1,73 @@
+use nalgebra::DMatrix;
+use rand::{Rng, RngExt};
+
+use crate::{PivotStrategy, SkewMatrix};
+
+/// Builds a random n x n skew matrix whose Pfaffian is `target`.
+///
+/// We draw the upper triangle uniformly from [-1, 1] and compute p = Pf(A)
+/// by partial-pivoting elimination (redrawing in the probability-zero case
+/// p = 0). Scaling the whole matrix by c scales the Pfaffian by c^(n/2), so
+/// c = |target / p|^(2/n) fixes the magnitude, and negating row and column 0
+/// flips the sign if needed. The Pfaffian is then `target` up to the
+/// rounding in computing p. A target of 0 gives the zero matrix.
+///
+/// Panics if n is odd, or if n = 0 and target isn't 1 (the 0x0 Pfaffian is
+/// always 1).
+pub fn random_with_pfaffian(n: usize, target: f64, rng: &mut impl Rng) -> SkewMatrix {
+    assert!(n.is_multiple_of(2), "The Pfaffian needs an even dimension.");
+    if n == 0 {
+        assert!(target == 1.0, "The 0x0 Pfaffian is always 1.");
+        return SkewMatrix::empty();
+    }
+
+    loop {
+        let mut data = DMatrix::<f64>::zeros(n, n);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let v = rng.random_range(-1.0..=1.0);
+                data[(i, j)] = v;
+                data[(j, i)] = -v;
+            }
+        }
+        let m = SkewMatrix::from_skew_unchecked(data);
+        let p = m.pfaffian_with_pivoting(PivotStrategy::Partial);
+        if p == 0.0 {
+            continue;
+        }
+
+        let c = (target / p).abs().powf(2.0 / n as f64);
+        let mut data = m.into_matrix() * c;
+        if (target < 0.0) != (p < 0.0) {
+            data.row_mut(0).neg_mut();
+            data.column_mut(0).neg_mut();
+        }
+        return SkewMatrix::from_skew_unchecked(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn pfaffian_hits_the_target() {
+        let mut rng = StdRng::seed_from_u64(149);
+        for n in [2, 4, 6, 8, 12] {
+            for target in [1.0, -3.5, 1e-6, 2e8] {
+                let pf = random_with_pfaffian(n, target, &mut rng).pfaffian();
+                assert!(
+                    (pf - target).abs() < 1e-10 * target.abs(),
+                    "n = {}: {} vs {}",
+                    n,
+                    pf,
+                    target
+                );
+            }
+        }
+        assert_eq!(random_with_pfaffian(6, 0.0, &mut rng).pfaffian(), 0.0);
+        assert_eq!(random_with_pfaffian(0, 1.0, &mut rng).pfaffian(), 1.0);
+    }
+}
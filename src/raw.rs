@@ -0,0 +1,90 @@
+use nalgebra::DMatrix;
+
+use crate::{PfaffianError, PivotStrategy, SkewMatrix};
+
+/// A dense matrix from an untrusted source that hasn't been checked yet.
+///
+/// Every public way of making a `SkewMatrix` validates its input, so a
+/// `SkewMatrix` is always square, even-sized and skew-symmetric. This is the
+/// other half of that typestate: a `RawMatrix` has no Pfaffian methods at
+/// all, and the only way to get one is `validate`, so code handed a
+/// `RawMatrix` can't forget the check.
+pub struct RawMatrix {
+    data: DMatrix<f64>,
+}
+
+impl RawMatrix {
+    pub fn new(data: DMatrix<f64>) -> Self {
+        Self { data }
+    }
+
+    /// Borrows the unchecked entries.
+    pub fn as_matrix(&self) -> &DMatrix<f64> {
+        &self.data
+    }
+
+    /// Checks the matrix as `SkewMatrix::from_matrix` does and, if it
+    /// passes, turns it into a `SkewMatrix`.
+    pub fn validate(self, tol: f64) -> Result<SkewMatrix, PfaffianError> {
+        SkewMatrix::from_matrix(self.data, tol)
+    }
+}
+
+impl From<DMatrix<f64>> for RawMatrix {
+    fn from(data: DMatrix<f64>) -> Self {
+        Self::new(data)
+    }
+}
+
+/// Splits a general square matrix M into its skew and symmetric parts and
+/// returns (Pf((M - Mᵀ) / 2), ‖(M + Mᵀ) / 2‖_F).
+///
+/// The second number is exactly what projecting onto the skew part throws
+/// away, so it says how far M was from skew to begin with (it is 0 for a
+/// skew M). Comparing it with ‖M‖_F gives a relative measure. An odd-sized
+/// M has a skew part with Pfaffian 0.
+/// Panics if M isn't square.
+pub fn pfaffian_of_skew_part(m: &DMatrix<f64>) -> (f64, f64) {
+    let (rows, cols) = m.shape();
+    assert_eq!(
+        rows, cols,
+        "Expected a square matrix, got {}x{}.",
+        rows, cols
+    );
+    let residual = ((m + m.transpose()) * 0.5).norm();
+    // from_matrix stores exactly the antisymmetric part, and with an
+    // infinite tolerance it only checks the size.
+    let pfaffian = match SkewMatrix::from_matrix(m.clone(), f64::INFINITY) {
+        Ok(skew) => skew.pfaffian_with_pivoting(PivotStrategy::Partial),
+        Err(_) => 0.0,
+    };
+    (pfaffian, residual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetric_contamination_is_reported_and_dropped() {
+        let skew = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+            .unwrap()
+            .into_matrix();
+        // A symmetric S with ‖S‖_F = √(4 · 0.5² + 2 · 0.25² + 2 · 1²) = √3.125.
+        let mut contamination = DMatrix::from_diagonal_element(4, 4, 0.5);
+        contamination[(0, 3)] = 0.25;
+        contamination[(3, 0)] = 0.25;
+        contamination[(1, 2)] = -1.0;
+        contamination[(2, 1)] = -1.0;
+
+        let (pf, residual) = pfaffian_of_skew_part(&(&skew + &contamination));
+        assert!((pf - 8.0).abs() < 1e-12);
+        assert!((residual - 3.125f64.sqrt()).abs() < 1e-12);
+
+        assert_eq!(pfaffian_of_skew_part(&skew), (8.0, 0.0));
+        assert_eq!(
+            pfaffian_of_skew_part(&DMatrix::identity(3, 3)),
+            (0.0, 3f64.sqrt())
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use std::ops::{Neg, Sub};
+
+use nalgebra::Scalar;
+use num_traits::{One, Zero};
+
+/// The scalars the unsigned matching sum (`hafnian_generic`) can work over:
+/// anything with 0, 1, + and *, but not necessarily -.
+///
+/// As with `Ring`, + and * are assumed commutative, and there is a blanket
+/// impl. Every `Ring` is a semiring, and so are types like `MaxPlus`, where
+/// there is no subtraction at all.
+pub trait Semiring: Scalar + Zero + One {}
+
+impl<T> Semiring for T where T: Scalar + Zero + One {}
+
+/// The scalars the division-free Pfaffian can work over: anything with
+/// 0, 1, +, - and *.
+///
+/// Multiplication is assumed to be commutative. The trait can't check that,
+/// but the Pfaffian isn't well defined over a non-commutative ring anyway.
+///
+/// There is a blanket impl, so f64, the integer types, `Polynomial`, and
+/// e.g. num-rational's `BigRational` are all rings without any extra work.
+///
+/// Quantities with statically typed units (as in `uom`) are not rings: a
+/// length times a length is an area, not a length, so they have no
+/// `Mul<Output = Self>`, and `SkewMatrix<Length>::pfaffian()` is a compile
+/// error. `UnitSkewMatrixN` carries the unit through the Pfaffian instead.
+pub trait Ring: Semiring + Neg<Output = Self> + Sub<Output = Self> {}
+
+impl<T> Ring for T where T: Scalar + Zero + One + Neg<Output = T> + Sub<Output = T> {}
@@ -0,0 +1,138 @@
+use crate::{Budget, PfaffianError, PivotStrategy, SkewMatrix};
+
+/// How far 2 ln|Pf(A)| may be from ln|det A| before an elimination result
+/// is rejected, roughly a relative mismatch of 1e-6 between Pf² and det.
+const DET_TOLERANCE: f64 = 1e-6;
+
+/// The exact fallback is only tried within this many steps, about a
+/// 230 x 230 matrix.
+const EXACT_BUDGET: u64 = 1 << 20;
+
+impl SkewMatrix {
+    /// Computes the Pfaffian, falling back to slower methods when the usual
+    /// elimination can't be trusted.
+    ///
+    /// First comes Parlett–Reid elimination in log form (`log_pfaffian`),
+    /// so the product of the pivots can't overflow or underflow partway
+    /// when the true Pfaffian is representable. That result is accepted if
+    /// it is finite and Pf² agrees with det(A) from an independent LU
+    /// factorization, compared as logs. Otherwise we try the exact
+    /// `pfaffian_best_effort`, if it fits in a modest budget, and finally
+    /// the orthogonal (Householder-based) canonical form, by way of
+    /// `pfaffian_deflated` with nothing deflated. That last one is
+    /// backward stable, but it is much slower, and if it is rejected as well
+    /// its value is returned anyway, being the most reliable of the lot.
+    pub fn pfaffian_robust(&self) -> f64 {
+        let (sign, log_abs) = self.log_pfaffian();
+        if self.matches_det(sign, log_abs, DET_TOLERANCE) {
+            return sign * log_abs.exp();
+        }
+
+        if let (pf, true) = self.pfaffian_best_effort(Budget::new(EXACT_BUDGET)) {
+            return pf;
+        }
+        self.pfaffian_deflated(0.0).1
+    }
+
+    /// Computes the Pfaffian by partial-pivoting elimination and checks it
+    /// against det(A) from an LU factorization, as `pfaffian_robust` does.
+    ///
+    /// The check passes if 2 ln|Pf| and ln|det A| are within `tol` of each
+    /// other (about a relative mismatch of `tol` between Pf² and det), or if
+    /// both say A is singular. Otherwise this fails with
+    /// `PfaffianError::Inconsistent`, which carries A in Matrix Market form
+    /// so the failure can be attached to a bug report and read back with
+    /// `read_matrix`.
+    pub fn pfaffian_checked(&self, tol: f64) -> Result<f64, PfaffianError> {
+        let (sign, log_abs) = self.log_pfaffian();
+        if self.matches_det(sign, log_abs, tol) {
+            return Ok(sign * log_abs.exp());
+        }
+        Err(PfaffianError::Inconsistent {
+            pfaffian: self.pfaffian_with_pivoting(PivotStrategy::Partial),
+            determinant: self.data.determinant(),
+            reproducer: self.to_matrix_market(),
+        })
+    }
+
+    /// Whether the (sign, ln|Pf|) pair of an elimination agrees with
+    /// ln|det A| to within `tol`.
+    fn matches_det(&self, sign: f64, log_abs: f64, tol: f64) -> bool {
+        match self.log_abs_det() {
+            Some(log_det) => sign != 0.0 && (2.0 * log_abs - log_det).abs() <= tol,
+            None => sign == 0.0,
+        }
+    }
+
+    /// ln|det A| from a partial-pivoting LU, or None if A is singular.
+    fn log_abs_det(&self) -> Option<f64> {
+        let lu = self.data.clone().lu();
+        let diagonal = lu.u().diagonal();
+        if diagonal.iter().any(|&u| u == 0.0) {
+            return None;
+        }
+        Some(diagonal.iter().map(|u| u.abs().ln()).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatrixFormat;
+
+    /// A 4x4 whose three expansion terms are around 2^60 and cancel to 8.
+    /// The elimination rounds those products away, while the entries are
+    /// integers well inside the exact path's reach.
+    fn cancelling() -> SkewMatrix {
+        let (a02, a13, a03, a12) = (
+            (1i64 << 30) + 8,
+            (1i64 << 30) + 18,
+            (1i64 << 29) + 14,
+            (1i64 << 29) + 20,
+        );
+        let a23 = (a02 * a13 - a03 * a12 + 1) as f64;
+        let values = [1.0, a02 as f64, a03 as f64, a12 as f64, a13 as f64, a23];
+        SkewMatrix::from_upper_triangle(4, &values).unwrap()
+    }
+
+    #[test]
+    fn falls_back_when_partial_pivoting_loses_the_pfaffian() {
+        let matrix = cancelling();
+        let e = |i, j| matrix.as_matrix()[(i, j)] as i128;
+        assert_eq!(e(0, 1) * e(2, 3) - e(0, 2) * e(1, 3) + e(0, 3) * e(1, 2), 8);
+
+        let (sign, log_abs) = matrix.log_pfaffian();
+        assert!((sign * log_abs.exp() - 8.0).abs() > 100.0);
+        assert_eq!(matrix.pfaffian_robust(), 8.0);
+    }
+
+    #[test]
+    fn keeps_the_elimination_result_when_it_checks_out() {
+        let values: Vec<f64> = (1..=15).map(|k| (k as f64).sqrt() - 2.0).collect();
+        let matrix = SkewMatrix::from_upper_triangle(6, &values).unwrap();
+        let (sign, log_abs) = matrix.log_pfaffian();
+        assert_eq!(matrix.pfaffian_robust(), sign * log_abs.exp());
+        assert!((matrix.pfaffian_robust() - matrix.pfaffian()).abs() < 1e-12);
+        assert_eq!(matrix.pfaffian_checked(1e-9), Ok(sign * log_abs.exp()));
+    }
+
+    #[test]
+    fn failed_check_dumps_a_reproducer_that_reads_back_exactly() {
+        let matrix = cancelling();
+        let Err(PfaffianError::Inconsistent {
+            pfaffian,
+            determinant,
+            reproducer,
+        }) = matrix.pfaffian_checked(DET_TOLERANCE)
+        else {
+            panic!("expected the Pf²/det check to fail");
+        };
+        assert_eq!(
+            pfaffian,
+            matrix.pfaffian_with_pivoting(PivotStrategy::Partial)
+        );
+        assert_eq!(determinant, matrix.as_matrix().determinant());
+        let read_back = MatrixFormat::MatrixMarket.parse(&reproducer).unwrap();
+        assert_eq!(read_back.as_matrix(), matrix.as_matrix());
+    }
+}
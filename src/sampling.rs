@@ -0,0 +1,125 @@
+use rand::{Rng, RngExt};
+use std::collections::HashMap;
+
+use crate::SkewMatrix;
+use crate::expansion::first_row_pairings;
+use crate::hafnian::subset_hafnian;
+
+impl SkewMatrix {
+    /// Draws a perfect matching with probability proportional to its weight
+    /// ∏ |A_ij|, the magnitude of its contribution to the Pfaffian.
+    ///
+    /// We pair off the first unmatched vertex i one step at a time, choosing
+    /// j with probability |A_ij| Z(rest) / Z(remaining), where Z(S) is the
+    /// total weight of the matchings of S (the Hafnian of |A| restricted to
+    /// S). Multiplying the step probabilities telescopes to weight / Z, so
+    /// the draw is exact. When every contribution has the same sign, as for a
+    /// Kasteleyn-oriented planar graph, Z(S) is just |Pf| of the submatrix.
+    ///
+    /// The Z(S) are memoized over subsets, so the first draw costs up to
+    /// 2^n subproblems. Pairs come back as (i, j) with i < j, in the order
+    /// they were chosen. Returns None if no matching has nonzero weight, as
+    /// for a zero matrix or a graph with no perfect matching, or if the
+    /// weights aren't usable (a NaN entry).
+    pub fn sample_matching(&self, rng: &mut impl Rng) -> Option<Vec<(usize, usize)>> {
+        let weights = self.data.abs();
+        let mut memo: HashMap<Vec<usize>, f64> = HashMap::new();
+        let mut remaining: Vec<usize> = (0..self.data.nrows()).collect();
+        let mut pairs = Vec::with_capacity(remaining.len() / 2);
+
+        let total = subset_hafnian(&weights, &remaining, &mut memo);
+        if total.is_nan() || total <= 0.0 {
+            return None;
+        }
+
+        while !remaining.is_empty() {
+            let i = remaining[0];
+            let mut options = Vec::with_capacity(remaining.len() - 1);
+            let mut sum = 0.0;
+            for (j, _, rest) in first_row_pairings(&remaining) {
+                let w = weights[(i, j)] * subset_hafnian(&weights, &rest, &mut memo);
+                sum += w;
+                options.push((j, w, rest));
+            }
+
+            // Walk the cumulative weights; fall back to the last nonzero
+            // option in case rounding leaves `target` just past the end.
+            let mut target = rng.random::<f64>() * sum;
+            let mut chosen = None;
+            for (j, w, rest) in options {
+                if w > 0.0 {
+                    chosen = Some((j, rest));
+                    if target < w {
+                        break;
+                    }
+                    target -= w;
+                }
+            }
+            let (j, rest) = chosen?;
+            pairs.push((i, j));
+            remaining = rest;
+        }
+        Some(pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn sampled_frequencies_match_weight_over_z() {
+        let values = [
+            1.0, -2.0, 0.5, 4.0, 3.0, -1.5, 2.0, 0.25, -3.0, 1.0, 2.5, -0.75, 1.25, -2.0, 0.5,
+        ];
+        let matrix = SkewMatrix::from_upper_triangle(6, &values).unwrap();
+        let mut expected: HashMap<Vec<(usize, usize)>, f64> = HashMap::new();
+        for (mut pairs, contribution) in matrix.matchings() {
+            pairs.sort();
+            expected.insert(pairs, contribution.abs());
+        }
+        let z: f64 = expected.values().sum();
+
+        let draws = 30_000;
+        let mut rng = StdRng::seed_from_u64(144);
+        let mut observed: HashMap<Vec<(usize, usize)>, usize> = HashMap::new();
+        for _ in 0..draws {
+            let mut pairs = matrix.sample_matching(&mut rng).unwrap();
+            pairs.sort();
+            *observed.entry(pairs).or_default() += 1;
+        }
+        assert!(observed.keys().all(|pairs| expected.contains_key(pairs)));
+
+        let chi_square: f64 = expected
+            .iter()
+            .map(|(pairs, weight)| {
+                let e = draws as f64 * weight / z;
+                let o = observed.get(pairs).copied().unwrap_or(0) as f64;
+                (o - e).powi(2) / e
+            })
+            .sum();
+        // The 0.1% critical value of chi-square with 15 - 1 degrees of freedom.
+        assert!(chi_square < 36.12, "chi-square {}", chi_square);
+    }
+
+    #[test]
+    fn no_weighted_matching_gives_none() {
+        let mut rng = StdRng::seed_from_u64(144);
+        let zero = SkewMatrix::from_upper_triangle(4, &[0.0; 6]).unwrap();
+        assert_eq!(zero.sample_matching(&mut rng), None);
+        // Two disjoint triangles: every edge is there, but no perfect matching.
+        let triangles = SkewMatrix::from_upper_triangle(
+            6,
+            &[
+                1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0,
+            ],
+        )
+        .unwrap();
+        assert_eq!(triangles.sample_matching(&mut rng), None);
+        let with_nan = SkewMatrix::from_upper_triangle_allow_non_finite(2, &[f64::NAN]).unwrap();
+        assert_eq!(with_nan.sample_matching(&mut rng), None);
+        assert_eq!(SkewMatrix::empty().sample_matching(&mut rng), Some(vec![]));
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use nalgebra::{DMatrix, DVector};
+
+use crate::expansion::{Expansion, expand_first_row};
+use crate::{PivotStrategy, SkewMatrix};
+
+/// The sign of a Pfaffian, for comparing without going through floats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sign {
+    Negative,
+    Zero,
+    Positive,
+}
+
+/// The outcome of `pfaffian_sign_rigorous`: a sign that holds for every
+/// matrix in the given bounds, or `Indeterminate` if it couldn't be pinned
+/// down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignResult {
+    Negative,
+    Positive,
+    Indeterminate,
+}
+
+/// Keeps track of the sign picked up by a chain of orthogonal congruences.
+///
+/// For any Q, Pf(QᵀAQ) = det(Q) Pf(A), and an orthogonal Q has det(Q) = ±1.
+/// Each Householder reflector contributes -1 and each rotation +1, which
+/// is easy to lose track of over a long sequence of updates. The tracker
+/// applies the transforms itself, so every transform is also recorded, and
+/// `sign()` gives the factor to multiply the original Pfaffian by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignTracker {
+    sign: f64,
+}
+
+impl Default for SignTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignTracker {
+    pub fn new() -> Self {
+        Self { sign: 1.0 }
+    }
+
+    /// The net det(Q) of everything applied so far, so that
+    /// Pf(current) = sign() * Pf(original).
+    pub fn sign(&self) -> f64 {
+        self.sign
+    }
+
+    /// Applies the reflector H = I - 2vvᵀ / vᵀv as HAH (H is symmetric, so this
+    /// is HᵀAH) and records its det(H) = -1.
+    pub fn householder(&mut self, m: &SkewMatrix, v: &DVector<f64>) -> SkewMatrix {
+        let n = v.len();
+        let h = DMatrix::<f64>::identity(n, n) - (v * v.transpose()) * (2.0 / v.norm_squared());
+        self.sign = -self.sign;
+        SkewMatrix::from_skew_unchecked(&h * &m.data * &h)
+    }
+
+    /// Applies a general orthogonal Q as QᵀAQ and records the sign of det(Q).
+    pub fn orthogonal(&mut self, m: &SkewMatrix, q: &DMatrix<f64>) -> SkewMatrix {
+        if q.determinant() < 0.0 {
+            self.sign = -self.sign;
+        }
+        SkewMatrix::from_skew_unchecked(q.transpose() * &m.data * q)
+    }
+}
+
+impl SkewMatrix {
+    /// The sign of the Pfaffian (±1) from the elimination pivots, without
+    /// multiplying out the magnitudes. None if the matrix is singular.
+    fn pivot_sign(&self) -> Option<f64> {
+        let (mut sign, pivots) = self.skew_pivots(PivotStrategy::Partial);
+        for p in pivots {
+            if p == 0.0 {
+                return None;
+            }
+            sign *= p.signum();
+        }
+        Some(sign)
+    }
+
+    /// The sign of Pf(A), from the signs of the elimination pivots and
+    /// swaps. The pivot magnitudes are never multiplied together, so this
+    /// works even when Pf(A) itself would overflow or underflow. An exactly
+    /// zero pivot gives `Sign::Zero`.
+    pub fn pfaffian_sign(&self) -> Sign {
+        match self.pivot_sign() {
+            None => Sign::Zero,
+            Some(s) if s < 0.0 => Sign::Negative,
+            Some(_) => Sign::Positive,
+        }
+    }
+
+    /// Checks whether Pf(self) and Pf(other) have the same sign, using only
+    /// the signs of each factorization's pivots. This can't overflow the way
+    /// comparing two full Pfaffians can. None if either matrix is singular.
+    pub fn same_pfaffian_sign(&self, other: &SkewMatrix) -> Option<bool> {
+        Some(self.pivot_sign()? == other.pivot_sign()?)
+    }
+}
+
+/// A closed interval [lo, hi] of reals.
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    /// Widens by one ulp on each side, which covers the rounding of the
+    /// operation that produced the endpoints, since Rust can't switch the
+    /// rounding mode.
+    fn outward(lo: f64, hi: f64) -> Self {
+        Self {
+            lo: lo.next_down(),
+            hi: hi.next_up(),
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self::outward(self.lo + other.lo, self.hi + other.hi)
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let products = [
+            self.lo * other.lo,
+            self.lo * other.hi,
+            self.hi * other.lo,
+            self.hi * other.hi,
+        ];
+        // f64::min would skip a NaN (from NaN bounds, or 0 · inf), so keep
+        // it instead and let it make the final answer Indeterminate.
+        if products.iter().any(|p| p.is_nan()) {
+            return Self {
+                lo: f64::NAN,
+                hi: f64::NAN,
+            };
+        }
+        let lo = products.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = products.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Self::outward(lo, hi)
+    }
+
+    fn neg(self) -> Self {
+        Self {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+/// Determines the sign of Pf(A) for every skew A with
+/// lower[i][j] ≤ A[i][j] ≤ upper[i][j] above the diagonal, if there is one.
+///
+/// This runs the expansion of `pfaffian()` (memoized over index subsets, so
+/// O(2^n · n) work) in interval arithmetic, rounding every operation
+/// outward. The final interval then contains Pf(A) for every matrix in the
+/// bounds, and if it lies strictly on one side of zero, that sign is
+/// certain. Otherwise the answer is `Indeterminate`: either some matrices
+/// in the bounds really have different signs, or the intervals grew too
+/// wide along the way. Only the upper triangles of the bounds are read.
+/// NaN bounds, and an odd size (where Pf is exactly 0), give
+/// `Indeterminate`.
+/// Panics if the bounds differ in size or some lower bound exceeds its
+/// upper bound.
+pub fn pfaffian_sign_rigorous(lower: &SkewMatrix, upper: &SkewMatrix) -> SignResult {
+    assert_eq!(
+        lower.data.shape(),
+        upper.data.shape(),
+        "The lower and upper bounds must be the same size."
+    );
+    let n = lower.data.nrows();
+    let mut pairs = (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j)));
+    if let Some((i, j)) = pairs.find(|&(i, j)| lower.data[(i, j)] > upper.data[(i, j)]) {
+        panic!("The lower bound exceeds the upper bound at ({}, {}).", i, j);
+    }
+
+    let entry = |i: usize, j: usize| Interval {
+        lo: lower.data[(i, j)],
+        hi: upper.data[(i, j)],
+    };
+    let indices: Vec<usize> = (0..n).collect();
+    let mut expansion = IntervalExpansion { entry };
+    let pf = expand_first_row(&mut expansion, &indices, &mut HashMap::new());
+    if pf.lo > 0.0 {
+        SignResult::Positive
+    } else if pf.hi < 0.0 {
+        SignResult::Negative
+    } else {
+        SignResult::Indeterminate
+    }
+}
+
+/// The expansion along the first row over intervals. `entry(i, j)` is
+/// only called with i < j.
+struct IntervalExpansion<F> {
+    entry: F,
+}
+
+impl<F: Fn(usize, usize) -> Interval> Expansion for IntervalExpansion<F> {
+    type Value = Interval;
+    type Sum = Interval;
+
+    fn empty(&mut self) -> Interval {
+        Interval { lo: 1.0, hi: 1.0 }
+    }
+
+    fn start(&mut self) -> Interval {
+        Interval { lo: 0.0, hi: 0.0 }
+    }
+
+    fn add_term(&mut self, sum: &mut Interval, i: usize, j: usize, positive: bool, sub: Interval) {
+        let term = (self.entry)(i, j).mul(sub);
+        *sum = sum.add(if positive { term } else { term.neg() });
+    }
+
+    fn finish(&mut self, sum: Interval) -> Interval {
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rigorous_sign_brackets_the_pfaffian() {
+        // Pf = 1·6 - 2·5 + 3·4 = 8, and stays positive within ±0.1.
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let shifted = |d: f64| {
+            let v: Vec<f64> = values.iter().map(|x| x + d).collect();
+            SkewMatrix::from_upper_triangle(4, &v).unwrap()
+        };
+        assert_eq!(
+            pfaffian_sign_rigorous(&shifted(0.0), &shifted(0.0)),
+            SignResult::Positive
+        );
+        assert_eq!(
+            pfaffian_sign_rigorous(&shifted(-0.1), &shifted(0.1)),
+            SignResult::Positive
+        );
+        assert_eq!(
+            pfaffian_sign_rigorous(&shifted(-3.0), &shifted(3.0)),
+            SignResult::Indeterminate
+        );
+        let negated: Vec<f64> = values.iter().map(|x| -x).collect();
+        let negated = SkewMatrix::from_upper_triangle(4, &negated).unwrap();
+        // Pf is homogeneous of degree 2, so negating every entry keeps it at 8.
+        assert_eq!(
+            pfaffian_sign_rigorous(&negated, &negated),
+            SignResult::Positive
+        );
+        // 1·(-6) - 2·5 + 3·4 = -4.
+        let flipped = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, -6.0]).unwrap();
+        assert_eq!(
+            pfaffian_sign_rigorous(&flipped, &flipped),
+            SignResult::Negative
+        );
+    }
+
+    #[test]
+    fn tracked_sign_recovers_the_pfaffian_after_householder_reflectors() {
+        let values = [
+            1.0, -2.0, 0.5, 4.0, 3.0, -1.5, 2.0, 0.25, -3.0, 1.0, 2.5, -0.75, 1.25, -2.0, 0.5,
+        ];
+        let original = SkewMatrix::from_upper_triangle(6, &values).unwrap();
+        let pf = original.pfaffian();
+
+        let mut tracker = SignTracker::new();
+        let mut current = original;
+        for k in 0..3 {
+            let v = DVector::from_fn(6, |i, _| ((i + 2 * k) % 5) as f64 - 1.5);
+            current = tracker.householder(&current, &v);
+        }
+        assert_eq!(tracker.sign(), -1.0);
+        assert!((current.pfaffian() - tracker.sign() * pf).abs() < 1e-9);
+
+        // Swapping two coordinates is orthogonal with det -1.
+        let mut swap = DMatrix::<f64>::identity(6, 6);
+        swap.swap_rows(0, 3);
+        current = tracker.orthogonal(&current, &swap);
+        assert_eq!(tracker.sign(), 1.0);
+        assert!((current.pfaffian() - pf).abs() < 1e-9);
+    }
+
+    #[test]
+    fn same_pfaffian_sign_tells_opposite_signs_apart() {
+        // Pf = 8 and Pf = 1·(-6) - 2·5 + 3·4 = -4.
+        let positive = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let negative =
+            SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, -6.0]).unwrap();
+        assert_eq!(positive.same_pfaffian_sign(&negative), Some(false));
+        assert_eq!(negative.same_pfaffian_sign(&negative), Some(true));
+        assert_eq!(positive.pfaffian_sign(), Sign::Positive);
+        assert_eq!(negative.pfaffian_sign(), Sign::Negative);
+
+        let singular = SkewMatrix::from_upper_triangle(4, &[1.0, 0.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
+        assert_eq!(positive.same_pfaffian_sign(&singular), None);
+        assert_eq!(singular.pfaffian_sign(), Sign::Zero);
+    }
+
+    #[test]
+    fn pfaffian_sign_covers_all_three_outcomes() {
+        // Pf = 8, then 1·(-6) - 2·5 + 3·4 = -4, then 0 with a zero row.
+        let positive = SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+        let negative =
+            SkewMatrix::from_upper_triangle(4, &[1.0, 2.0, 3.0, 4.0, 5.0, -6.0]).unwrap();
+        let zero = SkewMatrix::from_upper_triangle(4, &[0.0, 0.0, 0.0, 4.0, 5.0, 6.0]).unwrap();
+        assert_eq!(positive.pfaffian_sign(), Sign::Positive);
+        assert_eq!(negative.pfaffian_sign(), Sign::Negative);
+        assert_eq!(zero.pfaffian_sign(), Sign::Zero);
+
+        // Pf = -(1e200)^4 overflows, but its sign doesn't.
+        let mut huge = SkewMatrix::from_skew_unchecked(DMatrix::zeros(8, 8));
+        for k in 0..4 {
+            huge.set(2 * k, 2 * k + 1, if k == 2 { -1e200 } else { 1e200 });
+        }
+        assert_eq!(
+            huge.pfaffian_with_pivoting(PivotStrategy::Partial),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(huge.pfaffian_sign(), Sign::Negative);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use crate::SkewMatrix;
+use crate::expansion::{Expansion, Memo, NoMemo, PfaffianExpansion, expand_first_row};
+
+/// Instrumentation from a run of the recursive Pfaffian, see
+/// `SkewMatrix::pfaffian_with_stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PfaffianStats {
+    /// Total number of recursive calls, including the top-level one.
+    pub calls: u64,
+    /// Calls answered straight from the memo table.
+    pub memo_hits: u64,
+    /// Distinct subproblems stored in the memo table.
+    pub subproblems: usize,
+    /// Times the recursion bottomed out at an empty index set, i.e. complete
+    /// matchings walked. Without memoization this is (n-1)!!.
+    pub matchings: u64,
+}
+
+impl PfaffianStats {
+    /// The fraction of calls answered from the memo table.
+    pub fn hit_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.memo_hits as f64 / self.calls as f64
+        }
+    }
+}
+
+/// `pfaffian()`'s expansion, counting what the traversal does as it goes.
+struct CountedExpansion<'a> {
+    pfaffian: PfaffianExpansion<'a, f64>,
+    stats: PfaffianStats,
+}
+
+impl Expansion for CountedExpansion<'_> {
+    type Value = f64;
+    type Sum = f64;
+
+    fn empty(&mut self) -> f64 {
+        self.stats.matchings += 1;
+        self.pfaffian.empty()
+    }
+
+    fn start(&mut self) -> f64 {
+        self.pfaffian.start()
+    }
+
+    fn add_term(&mut self, sum: &mut f64, i: usize, j: usize, positive: bool, sub: f64) {
+        self.pfaffian.add_term(sum, i, j, positive, sub);
+    }
+
+    fn finish(&mut self, sum: f64) -> f64 {
+        self.pfaffian.finish(sum)
+    }
+
+    fn on_call(&mut self) {
+        self.stats.calls += 1;
+    }
+
+    fn on_memo_hit(&mut self) {
+        self.stats.memo_hits += 1;
+    }
+}
+
+impl SkewMatrix {
+    /// Computes the Pfaffian exactly like `pfaffian()`, and also reports how
+    /// much work the memoized recursion did. Handy for deciding when a matrix
+    /// is big enough to switch to `pfaffian_with_pivoting`.
+    pub fn pfaffian_with_stats(&self) -> (f64, PfaffianStats) {
+        let mut memo = HashMap::new();
+        let (pf, mut stats) = self.pfaffian_counted(&mut memo);
+        stats.subproblems = memo.len();
+        (pf, stats)
+    }
+
+    /// Like `pfaffian_with_stats`, but with the memo table switched off, so the
+    /// counts show the raw matching enumeration.
+    pub fn pfaffian_unmemoized_with_stats(&self) -> (f64, PfaffianStats) {
+        self.pfaffian_counted(&mut NoMemo)
+    }
+
+    fn pfaffian_counted(&self, memo: &mut impl Memo<f64>) -> (f64, PfaffianStats) {
+        let mut expansion = CountedExpansion {
+            pfaffian: PfaffianExpansion { data: &self.data },
+            stats: PfaffianStats::default(),
+        };
+        let initial_indices: Vec<usize> = (0..self.data.nrows()).collect();
+        let pf = expand_first_row(&mut expansion, &initial_indices, memo);
+        (pf, expansion.stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SkewMatrix;
+
+    #[test]
+    fn unmemoized_walk_visits_every_matching() {
+        let values: Vec<f64> = (1..=28).map(|k| k as f64).collect();
+        let matrix = SkewMatrix::from_upper_triangle(8, &values).unwrap();
+        let (pf, stats) = matrix.pfaffian_unmemoized_with_stats();
+        // 7!! = 105 complete matchings, and a call per node of the tree:
+        // 1 + 7 + 7·5 + 7·5·3 + 7·5·3·1.
+        assert_eq!(stats.matchings, 105);
+        assert_eq!(stats.calls, 1 + 7 + 35 + 105 + 105);
+        assert_eq!(stats.memo_hits, 0);
+        assert_eq!(pf, matrix.pfaffian());
+
+        let (memo_pf, memo_stats) = matrix.pfaffian_with_stats();
+        assert_eq!(memo_pf, pf);
+        assert!(memo_stats.memo_hits > 0);
+        assert!(memo_stats.calls < stats.calls);
+        // Every call that is neither a hit nor a complete matching stores
+        // a new subproblem.
+        assert_eq!(
+            memo_stats.subproblems as u64,
+            memo_stats.calls - memo_stats.memo_hits - memo_stats.matchings
+        );
+    }
+
+    #[test]
+    fn unmemoized_matching_count_is_the_double_factorial() {
+        let mut double_factorial = 1;
+        for n in (2..=10).step_by(2) {
+            double_factorial *= n as u64 - 1;
+            let values = vec![1.0; n * (n - 1) / 2];
+            let matrix = SkewMatrix::from_upper_triangle(n, &values).unwrap();
+            let (_, stats) = matrix.pfaffian_unmemoized_with_stats();
+            assert_eq!(stats.matchings, double_factorial, "n = {}", n);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::SkewMatrix;
+
+impl SkewMatrix {
+    /// The Pfaffian of every even-sized principal submatrix, keyed by its
+    /// rows/cols as a bitmask (bit i set for index i), the empty set
+    /// included with Pf = 1.
+    ///
+    /// Each Pfaffian comes from ones already computed, instead of its own
+    /// factorization: with i the smallest index in S,
+    /// Pf(A_S) = ∑_{j ∈ S, j > i} ± A_ij Pf(A_{S \ {i, j}}), the first-row
+    /// expansion, and S \ {i, j} is a smaller bitmask than S. Walking the
+    /// masks in increasing order therefore reaches every set one pair after
+    /// a set it was built from, and each costs O(|S|), for O(2^n · n) in
+    /// all against O(2^n n^3) for separate eliminations. There are 2^(n-1)
+    /// sets, kept in a dense table of 2^n entries while they are computed:
+    /// 128 MiB for the table alone at n = 24, plus the returned map, and
+    /// four times that at n = 26. Each entry is a plain sum of products,
+    /// with no pivoting and no division, so heavy cancellation can still
+    /// cost digits. Panics if n > 24.
+    pub fn all_even_submatrix_pfaffians(&self) -> HashMap<u64, f64> {
+        let n = self.data.nrows();
+        assert!(n <= 24, "Too many submatrices to enumerate for n = {}.", n);
+        let mut table = vec![0.0; 1 << n];
+        table[0] = 1.0;
+        for set in 1u64..(1 << n) {
+            if !set.count_ones().is_multiple_of(2) {
+                continue;
+            }
+            let i = set.trailing_zeros() as usize;
+            let rest = set & !(1 << i);
+            let mut total = 0.0;
+            let mut sign = 1.0;
+            for j in (i + 1)..n {
+                if rest & (1 << j) != 0 {
+                    total += sign * self.data[(i, j)] * table[(rest & !(1 << j)) as usize];
+                    sign = -sign;
+                }
+            }
+            table[set as usize] = total;
+        }
+
+        table
+            .into_iter()
+            .enumerate()
+            .filter(|&(set, _)| set.count_ones().is_multiple_of(2))
+            .map(|(set, pf)| (set as u64, pf))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_match_direct_submatrix_pfaffians() {
+        let values: Vec<f64> = (1..=15).map(|k| (k as f64).sqrt() - 2.0).collect();
+        let matrix = SkewMatrix::from_upper_triangle(6, &values).unwrap();
+        let all = matrix.all_even_submatrix_pfaffians();
+        assert_eq!(all.len(), 32);
+        assert_eq!(all[&0], 1.0);
+        assert_eq!(all[&0b1010], matrix.as_matrix()[(1, 3)]);
+
+        for set in [0b11_1111u64, 0b01_1011, 0b11_0110, 0b10_0001] {
+            let indices: Vec<usize> = (0..6).filter(|&i| set & (1 << i) != 0).collect();
+            let sub = matrix
+                .as_matrix()
+                .select_rows(&indices)
+                .select_columns(&indices);
+            let direct = SkewMatrix::from_skew_unchecked(sub).pfaffian();
+            assert!((all[&set] - direct).abs() < 1e-12, "set {:06b}", set);
+        }
+        assert!(!all.contains_key(&0b111));
+    }
+}
@@ -0,0 +1,67 @@
+use nalgebra::{DMatrix, DVector};
+
+use crate::{PivotStrategy, SkewMatrix};
+
+/// Computes the coefficients of the polynomial p(t) = Pf(A0 + t·A1).
+///
+/// Pf is a homogeneous polynomial of degree n/2 in the entries, so p has
+/// degree at most n/2. We evaluate it at n/2 + 1 Chebyshev nodes in [-1, 1]
+/// (which keeps the interpolation well conditioned) and solve for the
+/// coefficients. They are returned lowest order first, so
+/// p(t) = c[0] + c[1] t + ... + c[n/2] t^(n/2).
+/// Panics if A0 and A1 have different sizes.
+pub fn pfaffian_polynomial(a0: &SkewMatrix, a1: &SkewMatrix) -> Vec<f64> {
+    let n = a0.data.nrows();
+    assert_eq!(
+        n,
+        a1.data.nrows(),
+        "A0 and A1 must have the same dimensions."
+    );
+
+    let points = n / 2 + 1;
+    let nodes: Vec<f64> = (0..points)
+        .map(|k| (std::f64::consts::PI * (k as f64 + 0.5) / points as f64).cos())
+        .collect();
+
+    let vandermonde = DMatrix::from_fn(points, points, |r, c| nodes[r].powi(c as i32));
+    let samples = DVector::from_iterator(
+        points,
+        nodes.iter().map(|&t| {
+            let at_t = SkewMatrix::from_skew_unchecked(&a0.data + &a1.data * t);
+            at_t.pfaffian_with_pivoting(PivotStrategy::Partial)
+        }),
+    );
+
+    vandermonde
+        .lu()
+        .solve(&samples)
+        .expect("Chebyshev nodes are distinct, so the Vandermonde matrix is invertible.")
+        .iter()
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polynomial_matches_direct_pfaffians_along_the_sweep() {
+        let v0: Vec<f64> = (0..15).map(|k| ((k * 5) % 9) as f64 - 4.0).collect();
+        let v1: Vec<f64> = (0..15).map(|k| ((k * 2) % 5) as f64 - 2.0).collect();
+        let a0 = SkewMatrix::from_upper_triangle(6, &v0).unwrap();
+        let a1 = SkewMatrix::from_upper_triangle(6, &v1).unwrap();
+
+        let coeffs = pfaffian_polynomial(&a0, &a1);
+        assert_eq!(coeffs.len(), 4);
+        for t in [-2.0, -0.3, 0.0, 0.7, 1.5, 3.0] {
+            let p: f64 = coeffs.iter().rev().fold(0.0, |acc, c| acc * t + c);
+            let direct = SkewMatrix::from_skew_unchecked(&a0.data + &a1.data * t).pfaffian();
+            assert!(
+                (p - direct).abs() < 1e-9 * direct.abs().max(1.0),
+                "t = {}",
+                t
+            );
+        }
+    }
+}
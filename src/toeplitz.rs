@@ -0,0 +1,162 @@
+use nalgebra::{DMatrix, Matrix2};
+
+use crate::{PfaffianError, PivotStrategy, SkewMatrix};
+
+/// A skew-symmetric Toeplitz matrix, A_ij = c_{j-i}, stored as its first
+/// row (c_0, c_1, ..., c_{n-1}), with c_0 = 0 and c_{-k} = -c_k.
+///
+/// Only the n entries of the first row are kept, and `pfaffian()` uses the
+/// structure to run in O(n^2) instead of O(n^3).
+pub struct ToeplitzSkewMatrix {
+    n: usize,
+    first_row: Vec<f64>,
+}
+
+impl ToeplitzSkewMatrix {
+    /// Creates the matrix from its first row. Fails if the row has odd
+    /// length or a nonzero first entry (which would be on the diagonal).
+    pub fn new(first_row: Vec<f64>) -> Result<Self, PfaffianError> {
+        let n = first_row.len();
+        if !n.is_multiple_of(2) {
+            return Err(PfaffianError::OddDimension { n });
+        }
+        if n > 0 && first_row[0] != 0.0 {
+            return Err(PfaffianError::NotSkewSymmetric { row: 0, col: 0 });
+        }
+        Ok(Self { n, first_row })
+    }
+
+    /// c_k for any k in -(n-1)..n.
+    fn coefficient(&self, k: isize) -> f64 {
+        match k {
+            0 => 0.0,
+            k if k > 0 => self.first_row[k as usize],
+            k => -self.first_row[(-k) as usize],
+        }
+    }
+
+    /// R_d, the 2x2 block in block row I and block column I + d: grouping the
+    /// indices in pairs makes A block Toeplitz with these blocks.
+    fn block(&self, d: isize) -> Matrix2<f64> {
+        Matrix2::from_fn(|r, c| {
+            let k = 2 * d + c as isize - r as isize;
+            if k.unsigned_abs() < self.n {
+                self.coefficient(k)
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// Expands to the dense n x n matrix.
+    pub fn to_dense(&self) -> SkewMatrix {
+        let data = DMatrix::from_fn(self.n, self.n, |i, j| {
+            self.coefficient(j as isize - i as isize)
+        });
+        SkewMatrix::from_skew_unchecked(data)
+    }
+
+    /// Computes the Pfaffian in O(n^2) by a block Levinson recursion.
+    ///
+    /// With indices grouped in pairs, A is block Toeplitz with 2x2 blocks,
+    /// and the multichannel Levinson (Whittle) recursion grows forward and
+    /// backward predictors over its leading 2k x 2k submatrices A_k. The
+    /// backward prediction error E_k is the Schur complement of A_{k-1} in
+    /// A_k, a 2x2 skew block, so Pf(A_k) = Pf(A_{k-1}) · E_k[0][1] and
+    /// Pf(A) is the product of those entries.
+    ///
+    /// The recursion needs every leading 2k x 2k submatrix to be
+    /// nonsingular. If one isn't (a zero E_k before the end), this falls
+    /// back to dense partial-pivoting elimination.
+    pub fn pfaffian(&self) -> f64 {
+        let m = self.n / 2;
+        let mut pf = 1.0;
+        // forward[i] and backward[i] are the coefficients of block column i.
+        let mut forward: Vec<Matrix2<f64>> = vec![Matrix2::identity()];
+        let mut backward: Vec<Matrix2<f64>> = vec![Matrix2::identity()];
+        let mut forward_error = self.block(0);
+        let mut backward_error = self.block(0);
+
+        for k in 1..=m {
+            pf *= backward_error[(0, 1)];
+            if k == m {
+                break;
+            }
+            let (Some(forward_inv), Some(backward_inv)) =
+                (forward_error.try_inverse(), backward_error.try_inverse())
+            else {
+                return self
+                    .to_dense()
+                    .pfaffian_with_pivoting(PivotStrategy::Partial);
+            };
+
+            // What the predictors leave in the new block column (forward)
+            // and the new block column 0 (backward, shifted one block right).
+            let delta_f = (0..k).fold(Matrix2::<f64>::zeros(), |acc, i| {
+                acc + forward[i] * self.block((k - i) as isize)
+            });
+            let delta_b = (0..k).fold(Matrix2::<f64>::zeros(), |acc, i| {
+                acc + backward[i] * self.block(-(i as isize + 1))
+            });
+
+            let zero = Matrix2::<f64>::zeros();
+            let f_gain = delta_f * backward_inv;
+            let b_gain = delta_b * forward_inv;
+            let next_forward: Vec<_> = (0..=k)
+                .map(|i| {
+                    let a = if i < k { forward[i] } else { zero };
+                    let b = if i > 0 { backward[i - 1] } else { zero };
+                    a - f_gain * b
+                })
+                .collect();
+            let next_backward: Vec<_> = (0..=k)
+                .map(|i| {
+                    let a = if i < k { forward[i] } else { zero };
+                    let b = if i > 0 { backward[i - 1] } else { zero };
+                    b - b_gain * a
+                })
+                .collect();
+
+            forward_error -= f_gain * delta_b;
+            backward_error -= b_gain * delta_f;
+            forward = next_forward;
+            backward = next_backward;
+        }
+        pf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levinson_pfaffian_matches_the_dense_matrix() {
+        for n in (2..=12usize).step_by(2) {
+            let mut first_row: Vec<f64> =
+                (0..n).map(|k| ((k * 5 + 1) as f64).sin() + 0.5).collect();
+            first_row[0] = 0.0;
+            let toeplitz = ToeplitzSkewMatrix::new(first_row.clone()).unwrap();
+            let dense = toeplitz.to_dense();
+            assert_eq!(dense.as_matrix()[(1, n - 1)], first_row[n - 2]);
+            assert_eq!(dense.as_matrix()[(n - 1, 1)], -first_row[n - 2]);
+            let expected = dense.pfaffian();
+            assert!(
+                (toeplitz.pfaffian() - expected).abs() < 1e-10 * expected.abs().max(1.0),
+                "n = {}",
+                n
+            );
+        }
+
+        // c_1 = 0 makes the leading 2x2 block singular, which takes the
+        // dense fallback: Pf = c_1 c_1 - c_2 c_2 + c_3 c_1 = -1.
+        let singular_start = ToeplitzSkewMatrix::new(vec![0.0, 0.0, 1.0, 2.0]).unwrap();
+        assert_eq!(singular_start.pfaffian(), -1.0);
+
+        assert!(matches!(
+            ToeplitzSkewMatrix::new(vec![0.0, 1.0, 2.0]),
+            Err(PfaffianError::OddDimension { n: 3 })
+        ));
+        assert!(ToeplitzSkewMatrix::new(vec![1.0, 1.0]).is_err());
+    }
+}
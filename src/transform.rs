@@ -0,0 +1,123 @@
+use nalgebra::DMatrix;
+
+use crate::{PivotStrategy, SkewMatrix};
+
+/// The standard symplectic form J: block-diagonal with n/2 copies of
+///   0  1
+///  -1  0
+fn symplectic_form(n: usize) -> DMatrix<f64> {
+    let mut j = DMatrix::<f64>::zeros(n, n);
+    for k in (0..n).step_by(2) {
+        j[(k, k + 1)] = 1.0;
+        j[(k + 1, k)] = -1.0;
+    }
+    j
+}
+
+/// Whether cell j is coupled to cell i on a ring of `cells` cells:
+/// itself and its two neighbours (which coincide when the ring is short).
+fn ring_coupled(i: usize, j: usize, cells: usize) -> bool {
+    let offset = (j + cells - i) % cells;
+    offset == 0 || offset == 1 || offset == cells - 1
+}
+
+/// Tiles a skew block B around a ring of `blocks` cells, giving the
+/// block-circulant matrix whose (i, j) block is B when cells i and j are the
+/// same or neighbours, and zero otherwise.
+///
+/// This is S ⊗ B for the symmetric circulant S with first row
+/// (1, 1, 0, ..., 0, 1), and a symmetric matrix tensored with a skew one is
+/// skew, so antisymmetry holds across blocks as well as within them.
+/// Because of this structure the Pfaffian factors through the Fourier
+/// transform, see `pfaffian_block_circulant`.
+pub fn block_circulant(block: &SkewMatrix, blocks: usize) -> SkewMatrix {
+    let b = block.data.nrows();
+    let n = b * blocks;
+    let data = DMatrix::from_fn(n, n, |r, c| {
+        if ring_coupled(r / b, c / b, blocks) {
+            block.data[(r % b, c % b)]
+        } else {
+            0.0
+        }
+    });
+    SkewMatrix::from_skew_unchecked(data)
+}
+
+/// The Pfaffian of `block_circulant(block, blocks)`, without building it.
+///
+/// Diagonalizing S = Q D Qᵀ gives Pf(S ⊗ B) = det(S)^(b/2) · Pf(B)^N for a
+/// b x b block and N cells. S is circulant, so its eigenvalues come straight
+/// from the discrete Fourier transform of its first row:
+/// λ_k = ∑_d cos(2π d k / N) over the distinct coupled offsets d.
+pub fn pfaffian_block_circulant(block: &SkewMatrix, blocks: usize) -> f64 {
+    let half_b = (block.data.nrows() / 2) as i32;
+    let offsets: Vec<usize> = (0..blocks)
+        .filter(|&d| ring_coupled(0, d, blocks))
+        .collect();
+
+    let det_s: f64 = (0..blocks)
+        .map(|k| {
+            offsets
+                .iter()
+                .map(|&d| {
+                    let angle = 2.0 * std::f64::consts::PI * (d * k) as f64 / blocks as f64;
+                    angle.cos()
+                })
+                .sum::<f64>()
+        })
+        .product();
+
+    let pf_b = block.pfaffian_with_pivoting(PivotStrategy::Partial);
+    det_s.powi(half_b) * pf_b.powi(blocks as i32)
+}
+
+impl SkewMatrix {
+    /// Computes Pf(A + λJ), where J is the standard symplectic form
+    /// (block-diagonal [[0, 1], [-1, 0]] blocks).
+    ///
+    /// A + λI isn't skew-symmetric, but A + λJ is, for any λ. This form
+    /// only makes sense for even n, which SkewMatrix already guarantees.
+    pub fn pfaffian_symplectic_shift(&self, lambda: f64) -> f64 {
+        let n = self.data.nrows();
+        let shifted = SkewMatrix::from_skew_unchecked(&self.data + symplectic_form(n) * lambda);
+        shifted.pfaffian()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symplectic_shift_matches_the_directly_built_matrix() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let matrix = SkewMatrix::from_upper_triangle(4, &values).unwrap();
+        let lambda = 0.75;
+
+        // The upper triangle of A + λJ: J adds λ at (0, 1) and (2, 3).
+        let mut shifted = values;
+        shifted[0] += lambda;
+        shifted[5] += lambda;
+        let direct = SkewMatrix::from_upper_triangle(4, &shifted).unwrap();
+        assert_eq!(matrix.pfaffian_symplectic_shift(lambda), direct.pfaffian());
+    }
+
+    #[test]
+    fn block_circulant_is_skew_and_its_fast_pfaffian_matches_dense() {
+        let block = SkewMatrix::from_upper_triangle(4, &[1.0, 0.5, -2.0, 1.5, 0.25, 3.0]).unwrap();
+        for blocks in [2, 3, 4, 5] {
+            let tiled = block_circulant(&block, blocks);
+            assert_eq!(tiled.data.nrows(), 4 * blocks);
+            let rebuilt = SkewMatrix::from_matrix(tiled.data.clone(), 0.0);
+            assert!(rebuilt.is_ok(), "{} blocks", blocks);
+
+            let dense = tiled.pfaffian_with_pivoting(PivotStrategy::Partial);
+            let fast = pfaffian_block_circulant(&block, blocks);
+            assert!(
+                (fast - dense).abs() < 1e-9 * dense.abs().max(1.0),
+                "{} blocks",
+                blocks
+            );
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::ops::{Add, Mul};
+
+use num_traits::{One, Zero};
+
+/// A number in the max-plus (tropical) semiring, where "+" is max and "*"
+/// is ordinary addition.
+///
+/// Zero is -inf, the identity for max, and one is 0. So a sum over
+/// matchings of products of weights becomes the largest total weight of any
+/// matching: `hafnian_generic` on a `DMatrix<MaxPlus>` of edge weights is
+/// the weight of a maximum-weight perfect matching, with `MaxPlus::zero()`
+/// marking a missing edge and a result of -inf meaning there is no perfect
+/// matching at all. There's no subtraction, so this is a `Semiring` but not
+/// a `Ring`, and the signed `pfaffian()` doesn't apply: signs would cancel
+/// terms, which max can't.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct MaxPlus(pub f64);
+
+impl Add for MaxPlus {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.max(rhs.0))
+    }
+}
+
+impl Mul for MaxPlus {
+    type Output = Self;
+
+    // Tropical multiplication really is addition.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Zero for MaxPlus {
+    fn zero() -> Self {
+        Self(f64::NEG_INFINITY)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == f64::NEG_INFINITY
+    }
+}
+
+impl One for MaxPlus {
+    fn one() -> Self {
+        Self(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hafnian_generic;
+    use nalgebra::DMatrix;
+
+    /// The heaviest perfect matching of `vertices` by trying every pairing
+    /// of the first vertex, or None if there is none.
+    fn max_matching(weights: &DMatrix<f64>, vertices: &[usize]) -> Option<f64> {
+        let Some((&i, rest)) = vertices.split_first() else {
+            return Some(0.0);
+        };
+        (0..rest.len())
+            .filter(|&k| weights[(i, rest[k])].is_finite())
+            .filter_map(|k| {
+                let mut remaining = rest.to_vec();
+                let j = remaining.remove(k);
+                max_matching(weights, &remaining).map(|w| w + weights[(i.min(j), i.max(j))])
+            })
+            .max_by(f64::total_cmp)
+    }
+
+    #[test]
+    fn max_plus_hafnian_is_the_maximum_weight_matching() {
+        let n = 6;
+        let mut weights = DMatrix::from_fn(n, n, |i, j| ((i * 5 + j * 11) % 7) as f64 - 2.5);
+        weights[(0, 3)] = f64::NEG_INFINITY;
+        weights[(1, 4)] = f64::NEG_INFINITY;
+        let tropical = weights.map(MaxPlus);
+        let vertices: Vec<usize> = (0..n).collect();
+        let expected = max_matching(&weights, &vertices).unwrap();
+        assert_eq!(hafnian_generic(&tropical), MaxPlus(expected));
+
+        // Neither a star nor an odd number of vertices has a perfect matching.
+        let star = DMatrix::from_fn(4, 4, |i, j| {
+            MaxPlus(if i == 0 && j > 0 {
+                1.0
+            } else {
+                f64::NEG_INFINITY
+            })
+        });
+        assert!(hafnian_generic(&star).is_zero());
+        assert!(hafnian_generic(&DMatrix::from_element(3, 3, MaxPlus(1.0))).is_zero());
+    }
+}
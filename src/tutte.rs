@@ -0,0 +1,83 @@
+use nalgebra::DMatrix;
+use rand::{Rng, RngExt};
+
+use crate::integer::{FIRST_CRT_PRIME, eliminate_mod_p};
+
+/// Lovász's randomized test for whether a graph has a perfect matching.
+///
+/// The Tutte matrix of the graph has an independent indeterminate x_ij at
+/// A_ij = -A_ji for every edge i < j and zeros elsewhere, and its Pfaffian
+/// is the sum over perfect matchings of ± the product of their x's, so it
+/// is a nonzero polynomial exactly when a perfect matching exists. We
+/// substitute uniform random values from the field Z/p, with p the prime
+/// 2^64 - 59, and work out the Pfaffian there by exact elimination, so no
+/// rounding can fake a zero or hide one.
+///
+/// The error is one-sided. If this returns true, a perfect matching
+/// certainly exists. If it returns false, the polynomial (of degree n/2)
+/// may still be nonzero and have vanished at the random point, which by
+/// the Schwartz–Zippel lemma happens with probability at most
+/// n / (2p) ≈ n · 2^-65; repeating the test with fresh draws shrinks it
+/// further.
+///
+/// Any nonzero entry is an edge. The diagonal is ignored, since a loop
+/// can't be part of a matching, and a graph on an odd number of vertices
+/// never has one.
+/// Panics if the adjacency matrix isn't square or isn't symmetric.
+pub fn tutte_matrix_has_matching(adjacency: &DMatrix<u8>, rng: &mut impl Rng) -> bool {
+    let (n, cols) = adjacency.shape();
+    assert_eq!(n, cols, "Expected a square matrix, got {}x{}.", n, cols);
+    let edge = |i, j| adjacency[(i, j)] != 0;
+    let mut pairs = (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j)));
+    if let Some((i, j)) = pairs.find(|&(i, j)| edge(i, j) != edge(j, i)) {
+        panic!("Adjacency matrix is not symmetric at ({}, {}).", i, j);
+    }
+    if !n.is_multiple_of(2) {
+        return false;
+    }
+
+    let p = FIRST_CRT_PRIME;
+    let mut a = vec![vec![0; n]; n];
+    for (i, j) in (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j))) {
+        if edge(i, j) {
+            let x = rng.random_range(0..p);
+            a[i][j] = x;
+            a[j][i] = (p - x) % p;
+        }
+    }
+    eliminate_mod_p(a, p) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn graph(n: usize, edges: &[(usize, usize)]) -> DMatrix<u8> {
+        let mut adjacency = DMatrix::zeros(n, n);
+        for &(i, j) in edges {
+            adjacency[(i, j)] = 1;
+            adjacency[(j, i)] = 1;
+        }
+        adjacency
+    }
+
+    #[test]
+    fn matching_is_found_exactly_when_one_exists() {
+        let mut rng = StdRng::seed_from_u64(190);
+        let hexagon = graph(6, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)]);
+        // A claw centred on 0 plus a separate edge: no row is empty, but 0
+        // can only match one of its three leaves.
+        let claw = graph(6, &[(0, 1), (0, 2), (0, 3), (4, 5)]);
+        for _ in 0..20 {
+            assert!(tutte_matrix_has_matching(&hexagon, &mut rng));
+            assert!(!tutte_matrix_has_matching(&claw, &mut rng));
+        }
+        assert!(!tutte_matrix_has_matching(
+            &graph(3, &[(0, 1), (1, 2)]),
+            &mut rng
+        ));
+        assert!(tutte_matrix_has_matching(&graph(0, &[]), &mut rng));
+    }
+}
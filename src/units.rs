@@ -0,0 +1,129 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::SkewMatrixN;
+
+/// A number measured in U^E, for some unit U.
+///
+/// U is any marker type standing for a unit (`struct Metre;`, say), and the
+/// exponent is part of the type, so a length and an area are different
+/// types and can't be mixed up.
+pub struct Quantity<U, const E: i32> {
+    value: f64,
+    unit: PhantomData<U>,
+}
+
+impl<U, const E: i32> Quantity<U, E> {
+    /// The quantity `value` U^E.
+    pub fn new(value: f64) -> Self {
+        Self {
+            value,
+            unit: PhantomData,
+        }
+    }
+
+    /// The number of U^E.
+    pub fn value(self) -> f64 {
+        self.value
+    }
+
+    /// The exponent E of the unit.
+    pub fn exponent(self) -> i32 {
+        E
+    }
+}
+
+impl<U, const E: i32> Clone for Quantity<U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<U, const E: i32> Copy for Quantity<U, E> {}
+
+impl<U, const E: i32> PartialEq for Quantity<U, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<U, const E: i32> fmt::Debug for Quantity<U, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} u^{}", self.value, E)
+    }
+}
+
+/// A fixed-size skew matrix whose entries all carry the unit U.
+///
+/// The Pfaffian of an N x N matrix is homogeneous of degree N/2 in the
+/// entries, so `pfaffian()` returns a `Quantity<U, { N / 2 }>`: the entries
+/// of a `UnitSkewMatrixN<Metre, 4>` are lengths and its Pfaffian is an area.
+/// Every entry has the same type `Quantity<U, 1>`, so building a matrix
+/// from entries in different units is a compile error, as is asking for
+/// the Pfaffian of an odd-sized one.
+///
+/// The dynamic `SkewMatrix` can't do this, because its size and therefore
+/// the exponent are only known at runtime; and unit-carrying types like
+/// `uom`'s aren't a `Ring`, as a length times a length isn't a length.
+pub struct UnitSkewMatrixN<U, const N: usize> {
+    matrix: SkewMatrixN<N>,
+    unit: PhantomData<U>,
+}
+
+impl<U, const N: usize> UnitSkewMatrixN<U, N> {
+    /// Creates the matrix from its upper-triangular entries, laid out and
+    /// length-checked as in `SkewMatrixN::from_upper_triangle`.
+    pub fn from_upper_triangle<const M: usize>(values: [Quantity<U, 1>; M]) -> Self {
+        Self {
+            matrix: SkewMatrixN::from_upper_triangle(values.map(Quantity::value)),
+            unit: PhantomData,
+        }
+    }
+
+    /// The matrix of the entries' values in U.
+    pub fn values(&self) -> &SkewMatrixN<N> {
+        &self.matrix
+    }
+}
+
+// Stable Rust can't write `Quantity<U, { N / 2 }>` for a generic N, so the
+// impls are spelled out per dimension, alongside `even_dims!`.
+macro_rules! unit_pfaffians {
+    ($($n:literal => $half:literal),*) => {
+        $(
+            impl<U> UnitSkewMatrixN<U, $n> {
+                /// Computes the Pfaffian, in U^(N/2).
+                pub fn pfaffian(&self) -> Quantity<U, $half> {
+                    Quantity::new(self.matrix.pfaffian())
+                }
+            }
+        )*
+    };
+}
+
+unit_pfaffians!(
+    0 => 0, 2 => 1, 4 => 2, 6 => 3, 8 => 4, 10 => 5, 12 => 6, 14 => 7, 16 => 8
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Metre;
+
+    #[test]
+    fn pfaffian_of_4x4_lengths_is_an_area() {
+        let m = |value| Quantity::<Metre, 1>::new(value);
+        let matrix = UnitSkewMatrixN::<Metre, 4>::from_upper_triangle([
+            m(1.0),
+            m(2.0),
+            m(3.0),
+            m(4.0),
+            m(5.0),
+            m(6.0),
+        ]);
+        let pf: Quantity<Metre, 2> = matrix.pfaffian();
+        assert_eq!(pf.exponent(), 2);
+        assert_eq!(pf.value(), 1.0 * 6.0 - 2.0 * 5.0 + 3.0 * 4.0);
+    }
+}
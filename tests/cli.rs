@@ -0,0 +1,71 @@
+use pfaffian::{PivotStrategy, SkewMatrix};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn pfaffian(args: &[&str], file: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_pfaffian"))
+        .args(args)
+        .arg("--file")
+        .arg(file)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn compute_prints_the_pfaffian_of_a_fixture() {
+    for (name, args) in [
+        ("four.mtx", &["compute"][..]),
+        ("four.csv", &["compute"][..]),
+        ("four.csv", &["compute", "--format", "csv"][..]),
+    ] {
+        let printed: f64 = stdout(&pfaffian(args, &fixture(name)))
+            .trim()
+            .parse()
+            .unwrap();
+        assert!((printed - 8.0).abs() < 1e-12, "{}", name);
+    }
+}
+
+#[test]
+fn validate_accepts_a_skew_fixture_and_rejects_an_odd_one() {
+    let out = stdout(&pfaffian(&["validate"], &fixture("two.mtx")));
+    assert!(out.trim_end().ends_with("two.mtx: ok, 2x2 skew-symmetric"));
+
+    let odd = pfaffian(&["validate"], &fixture("odd.mtx"));
+    assert!(!odd.status.success());
+    assert!(String::from_utf8_lossy(&odd.stderr).starts_with("error:"));
+}
+
+#[test]
+fn compute_round_trips_a_matrix_written_as_matrix_market() {
+    let values: Vec<f64> = (0..28).map(|k| ((k * 7 + 2) as f64).sin()).collect();
+    let matrix = SkewMatrix::from_upper_triangle(8, &values).unwrap();
+    let file = Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli_round_trip.mtx");
+    std::fs::write(&file, matrix.to_matrix_market()).unwrap();
+
+    let validated = stdout(&pfaffian(&["validate"], &file));
+    assert!(validated.contains("ok, 8x8 skew-symmetric"));
+    let printed: f64 = stdout(&pfaffian(&["compute"], &file))
+        .trim()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        printed,
+        matrix.pfaffian_with_pivoting(PivotStrategy::Partial)
+    );
+}
@@ -0,0 +1,217 @@
+//! Throws arbitrary input, valid or not, at the public constructors and
+//! the Pfaffian entry points. Errors are fine; panics are not.
+
+use arbitrary::{Result, Unstructured};
+use nalgebra::DMatrix;
+use pfaffian::{
+    Budget, DeterministicReduction, LazySkewMatrix, LowRankSkewMatrix, MatrixFormat,
+    PackedSkewMatrix, PivotStrategy, RawMatrix, SkewMatrix, SkewMatrixBuilder, ToeplitzSkewMatrix,
+};
+use std::cell::Cell;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+/// How many inputs each run tries.
+const CASES: u64 = 400;
+
+thread_local! {
+    static FINITE_ONLY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// SplitMix64, so each case's bytes are reproducible from its seed alone.
+fn bytes_for(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed;
+    let mut bytes = Vec::with_capacity(len + 8);
+    while bytes.len() < len {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        bytes.extend_from_slice(&(z ^ (z >> 31)).to_le_bytes());
+    }
+    bytes
+}
+
+/// A float that is usually small and ordinary, and sometimes zero, huge,
+/// tiny, infinite or NaN. Each case draws from the finite ones only about
+/// half the time, so that constructors that reject non-finite entries
+/// still see matrices they accept.
+fn float(u: &mut Unstructured) -> Result<f64> {
+    let choice = u.int_in_range(0..=15)?;
+    let choice = if FINITE_ONLY.get() && (1..=4).contains(&choice) {
+        0
+    } else {
+        choice
+    };
+    Ok(match choice {
+        0 => 0.0,
+        1 => f64::NAN,
+        2 => f64::INFINITY,
+        3 => f64::NEG_INFINITY,
+        4 => f64::from_bits(u.arbitrary()?),
+        5 => 1e300 * f64::from(u.int_in_range(-3i8..=3)?),
+        6 => 1e-300 * f64::from(u.int_in_range(-3i8..=3)?),
+        _ => f64::from(u.int_in_range(-1000i32..=1000)?) / 64.0,
+    })
+}
+
+fn floats(u: &mut Unstructured, len: usize) -> Result<Vec<f64>> {
+    (0..len).map(|_| float(u)).collect()
+}
+
+/// A length near the right one, so both the happy path and the errors
+/// get exercised.
+fn near(u: &mut Unstructured, len: usize) -> Result<usize> {
+    Ok(match u.int_in_range(0..=7)? {
+        0 => len + 1,
+        1 => len.saturating_sub(1),
+        _ => len,
+    })
+}
+
+/// Every Pfaffian entry point that has no documented panic on a valid
+/// matrix. `run_case` keeps n small enough for the exponential ones.
+fn exercise(m: &SkewMatrix, u: &mut Unstructured) -> Result<()> {
+    let _ = m.pfaffian();
+    let _ = m.pfaffian_naive();
+    let _ = m.pfaffian_kahan();
+    let _ = m.pfaffian_with_stats();
+    let _ = m.pfaffian_unmemoized_with_stats();
+    let _ = m.pfaffian_grassmann();
+    let _ = m.pfaffian_via_matching_permanent();
+    let _ = m.perfect_matching_count();
+    let _ = m.pfaffian_approx_with_bound(u.int_in_range(0..=20)?);
+    let _ = m.matching_statistics();
+    let _ = m.all_even_submatrix_pfaffians();
+    let _ = m.pfaffian_bruteforce();
+    for strategy in [
+        PivotStrategy::None,
+        PivotStrategy::Partial,
+        PivotStrategy::Rook,
+    ] {
+        let _ = m.pfaffian_with_pivoting(strategy);
+        for reduction in [
+            DeterministicReduction::Sequential,
+            DeterministicReduction::PairwiseTree,
+        ] {
+            let _ = m.pfaffian_with_reduction(strategy, reduction);
+        }
+    }
+    let _ = m.log_pfaffian();
+    let _ = m.pfaffian_trace();
+    let _ = m.pfaffian_refined();
+    let _ = m.pfaffian_mixed_storage();
+    let _ = m.pfaffian_divide_conquer();
+    let _ = m.pfaffian_ca(2 * u.int_in_range(1..=4)?);
+    let _ = m.pfaffian_best_effort(Budget::new(u.int_in_range(0..=1000)?));
+    let _ = m.pfaffian_robust();
+    let _ = m.pfaffian_checked(float(u)?);
+    let _ = m.pfaffian_classified();
+    let _ = m.pfaffian_deflated(float(u)?);
+    let _ = m.pfaffian_sign();
+    let _ = m.pfaffian_symplectic_shift(float(u)?);
+    let _ = m.canonical_form();
+    let _ = m.factorize().log_pfaffian();
+    let _ = m.edge_weights(u.arbitrary()?);
+    let _ = m.to_matrix_market();
+    #[cfg(feature = "rand")]
+    {
+        use rand::SeedableRng;
+        let _ = m.sample_matching(&mut rand::rngs::StdRng::seed_from_u64(u.arbitrary()?));
+    }
+    Ok(())
+}
+
+fn run_case(u: &mut Unstructured) -> Result<()> {
+    FINITE_ONLY.set(u.arbitrary()?);
+    let n: usize = u.int_in_range(0..=8)?;
+    let triangle = n * n.saturating_sub(1) / 2;
+
+    let len = near(u, triangle)?;
+    let values = floats(u, len)?;
+    let allowing_non_finite = SkewMatrix::from_upper_triangle_allow_non_finite(n, &values);
+    if let Ok(m) = SkewMatrix::from_upper_triangle(n, &values) {
+        exercise(&m, u)?;
+    }
+    if let Ok(m) = allowing_non_finite {
+        exercise(&m, u)?;
+    }
+    if let Ok(packed) = PackedSkewMatrix::from_upper_triangle(n, &values) {
+        let _ = packed.pfaffian();
+    }
+
+    let rows = near(u, n)?;
+    let cols = near(u, n)?;
+    let dense = DMatrix::from_vec(rows, cols, floats(u, rows * cols)?);
+    let tol = float(u)?;
+    if let Ok(m) = SkewMatrix::from_matrix(dense.clone(), tol) {
+        exercise(&m, u)?;
+    }
+    if let Ok(m) = RawMatrix::new(dense.clone()).validate(tol) {
+        let _ = m.pfaffian();
+    }
+    let _ = SkewMatrix::from_commutator(&dense, &dense.transpose());
+    let flat_len = near(u, n * n)?;
+    let flat = floats(u, flat_len)?;
+    let _ = SkewMatrix::from_row_major(n, &flat, tol);
+    let _ = SkewMatrix::from_column_major(n, &flat, tol);
+
+    let mut builder = SkewMatrixBuilder::new(n);
+    for _ in 0..u.int_in_range(0..=6)? {
+        builder = builder.edge(u.int_in_range(0..=10)?, u.int_in_range(0..=10)?, float(u)?);
+    }
+    if let Ok(m) = builder.build() {
+        exercise(&m, u)?;
+    }
+
+    if let Ok(mut lazy) = LazySkewMatrix::new(n) {
+        for _ in 0..u.int_in_range(0..=6)? {
+            let i = u.int_in_range(0..=n.saturating_sub(1))?;
+            let j = u.int_in_range(0..=n.saturating_sub(1))?;
+            if i != j {
+                lazy.set(i, j, float(u)?);
+            }
+        }
+        let _ = lazy.pfaffian();
+    }
+    let first_row_len = near(u, n)?;
+    if let Ok(toeplitz) = ToeplitzSkewMatrix::new(floats(u, first_row_len)?) {
+        let _ = toeplitz.pfaffian();
+    }
+    let factor_cols = near(u, 2)?;
+    if let Ok(low_rank) = LowRankSkewMatrix::new(DMatrix::from_vec(
+        rows,
+        factor_cols,
+        floats(u, rows * factor_cols)?,
+    )) {
+        let _ = low_rank.pfaffian();
+    }
+
+    let text: String = u.arbitrary()?;
+    for format in [MatrixFormat::Csv, MatrixFormat::MatrixMarket] {
+        if let Ok(m) = format.parse(&text) {
+            let _ = m.pfaffian_with_pivoting(PivotStrategy::Partial);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn zero_matrix_does_not_panic() {
+    for n in [0, 2, 4, 6] {
+        let m =
+            SkewMatrix::from_upper_triangle(n, &vec![0.0; n * n.saturating_sub(1) / 2]).unwrap();
+        let mut u = Unstructured::new(&[]);
+        exercise(&m, &mut u).unwrap();
+    }
+}
+
+#[test]
+fn public_api_does_not_panic_on_arbitrary_input() {
+    for seed in 0..CASES {
+        let bytes = bytes_for(seed, 4096);
+        let outcome = catch_unwind(AssertUnwindSafe(|| {
+            let _ = run_case(&mut Unstructured::new(&bytes));
+        }));
+        assert!(outcome.is_ok(), "case {} panicked", seed);
+    }
+}
@@ -0,0 +1,37 @@
+use pfaffian::{MatrixFormat, PfaffianError, pfaffian_dir};
+use std::path::Path;
+
+fn fixtures() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"))
+}
+
+#[test]
+fn every_matrix_market_fixture_gets_its_own_result() {
+    let results = pfaffian_dir(fixtures(), MatrixFormat::MatrixMarket).unwrap();
+    let names: Vec<_> = results
+        .iter()
+        .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(names, ["four.mtx", "odd.mtx", "two.mtx"]);
+
+    assert!((results[0].1.clone().unwrap() - 8.0).abs() < 1e-12);
+    assert_eq!(results[1].1, Err(PfaffianError::OddDimension { n: 3 }));
+    assert_eq!(results[2].1, Ok(3.5));
+}
+
+#[test]
+fn only_files_with_the_format_extension_are_read() {
+    let results = pfaffian_dir(fixtures(), MatrixFormat::Csv).unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].0.ends_with("four.csv"));
+    assert!((results[0].1.clone().unwrap() - 8.0).abs() < 1e-12);
+}
+
+#[test]
+fn a_missing_directory_is_the_outer_error() {
+    let missing = fixtures().join("missing");
+    assert!(matches!(
+        pfaffian_dir(&missing, MatrixFormat::Csv),
+        Err(PfaffianError::Io { .. })
+    ));
+}
@@ -0,0 +1,15 @@
+//! Compile-time guarantees: things that must build, and things that must not.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/even_dimension_pfaffian.rs");
+    t.compile_fail("tests/ui/odd_dimension_pfaffian.rs");
+    t.pass("tests/ui/raw_matrix_validated.rs");
+    t.compile_fail("tests/ui/raw_matrix_pfaffian.rs");
+    t.compile_fail("tests/ui/sealed_even_dim.rs");
+    t.compile_fail("tests/ui/wrong_upper_triangle_length.rs");
+    t.pass("tests/ui/unit_pfaffian_exponent.rs");
+    t.compile_fail("tests/ui/unit_pfaffian_wrong_exponent.rs");
+    t.compile_fail("tests/ui/mixed_units.rs");
+}
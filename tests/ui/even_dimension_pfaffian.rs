@@ -0,0 +1,8 @@
+use pfaffian::SkewMatrixN;
+
+fn main() {
+    let m = SkewMatrixN::<4>::from_upper_triangle([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(m.pfaffian(), 8.0);
+    assert_eq!(m.as_matrix()[(0, 1)], 1.0);
+    assert_eq!(m.into_matrix()[(1, 0)], -1.0);
+}
@@ -0,0 +1,11 @@
+use pfaffian::{Quantity, UnitSkewMatrixN};
+
+struct Metre;
+struct Second;
+
+fn main() {
+    let m = Quantity::<Metre, 1>::new;
+    let s = Quantity::<Second, 1>::new;
+    // Every entry has to carry the same unit.
+    let _ = UnitSkewMatrixN::<Metre, 4>::from_upper_triangle([m(1.0), m(2.0), s(3.0), m(4.0), m(5.0), m(6.0)]);
+}
@@ -0,0 +1,6 @@
+use pfaffian::SkewMatrixN;
+
+fn main() {
+    let m = SkewMatrixN::<3>::from_upper_triangle([1.0, 2.0, 3.0]);
+    m.pfaffian();
+}
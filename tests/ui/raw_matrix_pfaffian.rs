@@ -0,0 +1,8 @@
+use nalgebra::DMatrix;
+use pfaffian::RawMatrix;
+
+fn main() {
+    let raw = RawMatrix::new(DMatrix::from_row_slice(2, 2, &[0.0, 1.0, -1.0, 0.0]));
+    // Unvalidated input has no Pfaffian; it has to go through validate().
+    raw.pfaffian();
+}
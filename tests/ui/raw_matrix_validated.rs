@@ -0,0 +1,7 @@
+use nalgebra::DMatrix;
+use pfaffian::RawMatrix;
+
+fn main() {
+    let raw = RawMatrix::new(DMatrix::from_row_slice(2, 2, &[0.0, 1.0, -1.0, 0.0]));
+    assert_eq!(raw.validate(0.0).unwrap().pfaffian(), 1.0);
+}
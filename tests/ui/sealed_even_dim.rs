@@ -0,0 +1,9 @@
+use pfaffian::EvenDim;
+
+// EvenDim is sealed, so no other crate can add a dimension to it, odd or
+// otherwise.
+struct Three;
+
+impl EvenDim for Three {}
+
+fn main() {}
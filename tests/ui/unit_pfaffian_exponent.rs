@@ -0,0 +1,12 @@
+use pfaffian::{Quantity, UnitSkewMatrixN};
+
+struct Metre;
+
+fn main() {
+    let m = Quantity::<Metre, 1>::new;
+    let matrix =
+        UnitSkewMatrixN::<Metre, 4>::from_upper_triangle([m(1.0), m(2.0), m(3.0), m(4.0), m(5.0), m(6.0)]);
+    // The Pfaffian of 4x4 lengths is an area; a length would not type-check.
+    let area: Quantity<Metre, 2> = matrix.pfaffian();
+    assert_eq!(area.value(), 8.0);
+}
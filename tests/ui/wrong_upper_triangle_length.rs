@@ -0,0 +1,6 @@
+use pfaffian::SkewMatrix4;
+
+fn main() {
+    // A 4x4 upper triangle has 6 entries, not 5.
+    let _ = SkewMatrix4::from_upper_triangle([1.0, 2.0, 3.0, 4.0, 5.0]);
+}